@@ -1,12 +1,7 @@
 use std::{collections::HashMap, error::Error, sync::OnceLock};
 
-use nom::{
-    branch::alt,
-    character::complete::{char, newline},
-    combinator::value,
-    multi::{many1, separated_list1},
-    IResult,
-};
+use grid::{parse_grid, Grid, Position, OFFSETS_4};
+use nom::{branch::alt, character::complete::char, combinator::value, IResult};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 enum Tile {
@@ -39,12 +34,8 @@ fn parse_tile(input: &str) -> IResult<&str, Tile> {
     ))(input)
 }
 
-fn parse_tile_line(input: &str) -> IResult<&str, Vec<Tile>> {
-    many1(parse_tile)(input)
-}
-
-fn parse_tile_map(input: &str) -> IResult<&str, Vec<Vec<Tile>>> {
-    separated_list1(newline, parse_tile_line)(input)
+fn parse_tile_map(input: &str) -> IResult<&str, Grid<Tile>> {
+    parse_grid(parse_tile)(input)
 }
 
 const TOP: isize = -1;
@@ -57,10 +48,12 @@ const BOTTOM_EDGE: [Pipe; 3] = [Vertical, TopLeft, TopRight];
 const LEFT_EDGE: [Pipe; 3] = [Horizontal, TopRight, BottomRight];
 const RIGHT_EDGE: [Pipe; 3] = [Horizontal, TopLeft, BottomLeft];
 
-static PIPES_ADJACENCY_RULES: OnceLock<HashMap<Pipe, HashMap<(isize, isize), [Pipe; 3]>>> =
-    OnceLock::new();
+/// Pipes which can be connected by the shift
+type AdjacencyRules = HashMap<(isize, isize), [Pipe; 3]>;
+
+static PIPES_ADJACENCY_RULES: OnceLock<HashMap<Pipe, AdjacencyRules>> = OnceLock::new();
 
-fn pipes_adjacency_rules() -> &'static HashMap<Pipe, HashMap<(isize, isize), [Pipe; 3]>> {
+fn pipes_adjacency_rules() -> &'static HashMap<Pipe, AdjacencyRules> {
     PIPES_ADJACENCY_RULES.get_or_init(|| {
         HashMap::from_iter([
             (
@@ -91,9 +84,9 @@ fn pipes_adjacency_rules() -> &'static HashMap<Pipe, HashMap<(isize, isize), [Pi
     })
 }
 
-static START_TILE_ADJACENCY_RULES: OnceLock<HashMap<(isize, isize), [Pipe; 3]>> = OnceLock::new();
+static START_TILE_ADJACENCY_RULES: OnceLock<AdjacencyRules> = OnceLock::new();
 
-fn start_tile_adjacency_rules() -> &'static HashMap<(isize, isize), [Pipe; 3]> {
+fn start_tile_adjacency_rules() -> &'static AdjacencyRules {
     START_TILE_ADJACENCY_RULES.get_or_init(|| {
         HashMap::from_iter([
             ((TOP, 0), TOP_EDGE),
@@ -104,64 +97,51 @@ fn start_tile_adjacency_rules() -> &'static HashMap<(isize, isize), [Pipe; 3]> {
     })
 }
 
-fn vertical_horizontal_shifts(
-    (i, j): (isize, isize),
-    (lines, columns): (isize, isize),
-) -> impl Iterator<Item = (isize, isize)> {
-    [(-1, 0), (1, 0), (0, -1), (0, 1)]
+/// Neighbour positions along with the shifts leading to them
+fn vertical_horizontal_neighbors(
+    position: Position,
+    tile_map: &Grid<Tile>,
+) -> impl Iterator<Item = ((isize, isize), Position)> + '_ {
+    OFFSETS_4
         .into_iter()
-        .filter(move |(m, n)| *m + i >= 0 && *n + j >= 0 && *m + i < lines && *n + j < columns)
+        .filter_map(move |offset| Some((offset, tile_map.shift(position, offset)?)))
 }
 
 fn find_adjacent_pipes(
-    (i, j): (usize, usize),
-    tile_map: &Vec<Vec<Tile>>,
-) -> (((usize, usize), Pipe), ((usize, usize), Pipe)) {
-    let (i, j) = (i as isize, j as isize);
-    let v: Vec<((usize, usize), Pipe)> = vertical_horizontal_shifts(
-        (i, j),
-        (tile_map.len() as isize, tile_map[0].len() as isize),
-    )
-    .filter_map(|(m, n)| {
-        let adjacency_rules = &start_tile_adjacency_rules()[&(m, n)];
-        let tile = tile_map[(m + i) as usize][(n + j) as usize].clone();
-        if let Tile::Pipe(pipe) = tile {
-            if adjacency_rules.contains(&pipe) {
-                return Some((((m + i) as usize, (n + j) as usize), pipe));
+    position: Position,
+    tile_map: &Grid<Tile>,
+) -> ((Position, Pipe), (Position, Pipe)) {
+    let v: Vec<(Position, Pipe)> = vertical_horizontal_neighbors(position, tile_map)
+        .filter_map(|(offset, neighbor)| {
+            let adjacency_rules = &start_tile_adjacency_rules()[&offset];
+            if let Tile::Pipe(pipe) = tile_map[neighbor] {
+                if adjacency_rules.contains(&pipe) {
+                    return Some((neighbor, pipe));
+                }
             }
-        }
-        None
-    })
-    .collect();
+            None
+        })
+        .collect();
     (v[0], v[1])
 }
 
 fn next_pipe(
-    previous_position: (usize, usize),
-    current_position: ((usize, usize), Pipe),
-    tile_map: &Vec<Vec<Tile>>,
-) -> ((usize, usize), Pipe) {
-    let previous_position = (previous_position.0 as isize, previous_position.1 as isize);
-    let (i, j) = (
-        current_position.0 .0 as isize,
-        current_position.0 .1 as isize,
-    );
-    let v: Vec<((usize, usize), Pipe)> = vertical_horizontal_shifts(
-        (i, j),
-        (tile_map.len() as isize, tile_map[0].len() as isize),
-    )
-    .filter(|(m, n)| previous_position != (*m + i, *n + j))
-    .filter_map(|(m, n)| {
-        let adjacency_rules = &pipes_adjacency_rules()[&current_position.1].get(&(m, n))?;
-        let tile = tile_map[(m + i) as usize][(n + j) as usize].clone();
-        if let Tile::Pipe(pipe) = tile {
-            if adjacency_rules.contains(&pipe) {
-                return Some((((m + i) as usize, (n + j) as usize), pipe));
+    previous_position: Position,
+    current_position: (Position, Pipe),
+    tile_map: &Grid<Tile>,
+) -> (Position, Pipe) {
+    let v: Vec<(Position, Pipe)> = vertical_horizontal_neighbors(current_position.0, tile_map)
+        .filter(|(_, neighbor)| previous_position != *neighbor)
+        .filter_map(|(offset, neighbor)| {
+            let adjacency_rules = &pipes_adjacency_rules()[&current_position.1].get(&offset)?;
+            if let Tile::Pipe(pipe) = tile_map[neighbor] {
+                if adjacency_rules.contains(&pipe) {
+                    return Some((neighbor, pipe));
+                }
             }
-        }
-        None
-    })
-    .collect();
+            None
+        })
+        .collect();
 
     v[0]
 }
@@ -171,14 +151,8 @@ fn main() -> Result<(), Box<dyn Error>> {
     let tile_map = parse_tile_map(input)?.1;
 
     let start_position = tile_map
-        .iter()
-        .enumerate()
-        .filter_map(|(i, line)| {
-            line.iter()
-                .position(|tile| *tile == Tile::Start)
-                .map(|j| (i, j))
-        })
-        .collect::<Vec<(usize, usize)>>()[0];
+        .position(|tile| *tile == Tile::Start)
+        .ok_or("There is no start tile")?;
 
     let (first, second) = find_adjacent_pipes(start_position, &tile_map);
     let (mut first, mut second) = ((start_position, first), (start_position, second));
@@ -0,0 +1,90 @@
+//! Games read line by line as they're written
+
+use std::{
+    io::{self, BufRead},
+    thread,
+    time::Duration,
+};
+
+use crate::game::Game;
+
+pub type ParseError = nom::Err<nom::error::Error<String>>;
+
+/// Passes every non-blank line of the `reader` parsed as a game to `on_game` along with the
+/// trimmed line. Partial line at the end of the reader is handled as the last one, unless the
+/// `poll_interval` is given: the reader is polled then till the line is complete and the
+/// reading never ends since more lines may be appended.
+pub fn follow_games(
+    mut reader: impl BufRead,
+    poll_interval: Option<Duration>,
+    mut on_game: impl FnMut(&str, Result<Game, ParseError>),
+) -> io::Result<()> {
+    let mut line = String::new();
+
+    loop {
+        let is_eof = reader.read_line(&mut line)? == 0;
+        if !line.ends_with('\n') && (poll_interval.is_some() || !is_eof) {
+            // Wait till the line is completely written
+            if is_eof {
+                thread::sleep(poll_interval.unwrap_or_default());
+            }
+            continue;
+        }
+
+        if !line.trim().is_empty() {
+            on_game(line.trim(), line.parse());
+        }
+        line.clear();
+
+        if is_eof {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read};
+
+    use super::*;
+
+    /// Ids of the followed games, `None` for the lines which couldn't be parsed
+    fn follow(input: impl BufRead) -> Vec<Result<u64, String>> {
+        let mut games = Vec::new();
+        follow_games(input, None, |line, game| {
+            games.push(game.map(|game| game.id).map_err(|_| line.to_owned()))
+        })
+        .unwrap();
+        games
+    }
+
+    #[test]
+    fn partial_last_line_is_handled_at_the_end() {
+        assert_eq!(
+            follow(Cursor::new("Game 1: 1 red\nGame 2: 2 blue")),
+            vec![Ok(1), Ok(2)]
+        );
+        // Line split between the reads is joined
+        let chunks = Cursor::new("Game 1: 1 red\nGame 2").chain(Cursor::new(": 2 blue\n"));
+        assert_eq!(follow(io::BufReader::new(chunks)), vec![Ok(1), Ok(2)]);
+    }
+
+    #[test]
+    fn blank_lines_are_skipped() {
+        assert_eq!(
+            follow(Cursor::new(
+                "\n\n  \nGame 1: 1 red\r\n\n\t\nGame 2: 2 blue\n\n"
+            )),
+            vec![Ok(1), Ok(2)]
+        );
+        assert!(follow(Cursor::new("")).is_empty());
+    }
+
+    #[test]
+    fn bad_line_does_not_stop_following() {
+        assert_eq!(
+            follow(Cursor::new("Game 1: 1 red\n  nonsense \nGame 2: 2 blue\n")),
+            vec![Ok(1), Err("nonsense".to_owned()), Ok(2)]
+        );
+    }
+}
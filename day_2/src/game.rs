@@ -0,0 +1,207 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
+use nom::{
+    bytes::complete::tag,
+    character::complete::{alpha1, char, digit1, space0, space1},
+    combinator::{all_consuming, map, map_res},
+    multi::separated_list1,
+    sequence::{delimited, separated_pair, terminated, tuple},
+    IResult,
+};
+
+/// Colours which go first (and in this order) in a normalised draw
+const CANONICAL_COLORS_ORDER: [&str; 3] = ["red", "green", "blue"];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cubes {
+    pub quantity: u64,
+    pub color: String,
+}
+
+/// Subset of cubes revealed at once
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Draw(pub Vec<Cubes>);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Game {
+    pub id: u64,
+    pub draws: Vec<Draw>,
+}
+
+impl Draw {
+    /// Quantity of the cubes of the `color` revealed in the draw, saturated at `u64::MAX`
+    pub fn quantity(&self, color: &str) -> u64 {
+        self.0
+            .iter()
+            .filter(|cubes| cubes.color == color)
+            .fold(0, |quantity, cubes| quantity.saturating_add(cubes.quantity))
+    }
+
+    /// Quantity of all the cubes revealed in the draw, saturated at `u64::MAX`
+    pub fn total(&self) -> u64 {
+        self.0
+            .iter()
+            .fold(0, |total, cubes| total.saturating_add(cubes.quantity))
+    }
+
+    /// Returns the draw with its cubes ordered as "red, green, blue", other colours go
+    /// afterwards in alphabetical order
+    pub fn normalized(&self) -> Self {
+        let mut cubes = self.0.clone();
+        cubes.sort_by_key(|cubes| {
+            let position = CANONICAL_COLORS_ORDER
+                .iter()
+                .position(|color| *color == cubes.color)
+                .unwrap_or(CANONICAL_COLORS_ORDER.len());
+            (position, cubes.color.clone())
+        });
+        Draw(cubes)
+    }
+}
+
+impl Game {
+    /// Product of the minimal quantities of the cubes of each colour making the game possible
+    pub fn power(&self) -> u64 {
+        let mut max_cubes_quantity: HashMap<&str, u64> = HashMap::new();
+        for cubes in self.draws.iter().flat_map(|draw| &draw.0) {
+            let max_cube_quantity = max_cubes_quantity.entry(&cubes.color).or_default();
+            *max_cube_quantity = (*max_cube_quantity).max(cubes.quantity);
+        }
+        max_cubes_quantity.values().product()
+    }
+
+    pub fn normalized(&self) -> Self {
+        Game {
+            id: self.id,
+            draws: self.draws.iter().map(Draw::normalized).collect(),
+        }
+    }
+}
+
+impl fmt::Display for Cubes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.quantity, self.color)
+    }
+}
+
+impl fmt::Display for Draw {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, cubes) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{cubes}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Game {}: ", self.id)?;
+        for (i, draw) in self.draws.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{draw}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Game {
+    type Err = nom::Err<nom::error::Error<String>>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        all_consuming(parse_game)(s.trim())
+            .map(|(_, game)| game)
+            .map_err(|err| err.to_owned())
+    }
+}
+
+fn parse_number(input: &str) -> IResult<&str, u64> {
+    map_res(digit1, |s: &str| s.parse::<u64>())(input)
+}
+
+/// Separator surrounded by an arbitrary amount of spaces
+fn parse_separator<'a>(separator: char) -> impl FnMut(&'a str) -> IResult<&'a str, char> {
+    delimited(space0, char(separator), space0)
+}
+
+fn parse_game_header(input: &str) -> IResult<&str, u64> {
+    delimited(
+        tuple((tag("Game"), space1)),
+        parse_number,
+        tuple((space0, char(':'), space0)),
+    )(input)
+}
+
+fn parse_cubes(input: &str) -> IResult<&str, Cubes> {
+    map(
+        separated_pair(parse_number, space1, alpha1),
+        |(quantity, color): (u64, &str)| Cubes {
+            quantity,
+            color: color.to_owned(),
+        },
+    )(input)
+}
+
+fn parse_draw(input: &str) -> IResult<&str, Draw> {
+    map(separated_list1(parse_separator(','), parse_cubes), Draw)(input)
+}
+
+/// Parses a single `Game N: a red, b blue; ...` line, spaces around the separators are optional
+pub fn parse_game(input: &str) -> IResult<&str, Game> {
+    let (input, id) = parse_game_header(input)?;
+    let (input, draws) =
+        terminated(separated_list1(parse_separator(';'), parse_draw), space0)(input)?;
+    Ok((input, Game { id, draws }))
+}
+
+/// Parses every non-empty line of the `input` as a game
+pub fn parse_games(input: &str) -> Result<Vec<Game>, nom::Err<nom::error::Error<String>>> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn game_strategy() -> impl Strategy<Value = Game> {
+        let cubes =
+            (0..100_u64, "[a-z]{1,8}").prop_map(|(quantity, color)| Cubes { quantity, color });
+        let draw = prop::collection::vec(cubes, 1..5).prop_map(Draw);
+        (0..1000_u64, prop::collection::vec(draw, 1..6)).prop_map(|(id, draws)| Game { id, draws })
+    }
+
+    proptest! {
+        #[test]
+        fn parse_serialize_round_trip(game in game_strategy()) {
+            prop_assert_eq!(game.to_string().parse::<Game>().unwrap(), game);
+        }
+
+        #[test]
+        fn normalization_is_idempotent(game in game_strategy()) {
+            let normalized = game.normalized();
+            prop_assert_eq!(normalized.normalized(), normalized.clone());
+            prop_assert_eq!(normalized.to_string().parse::<Game>().unwrap(), normalized);
+        }
+    }
+
+    #[test]
+    fn normalization_fixes_order_and_spacing() {
+        let game: Game = "Game 3 :8 green,6 blue , 20 red;5 blue;  4 red ,13 green"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            game.normalized().to_string(),
+            "Game 3: 20 red, 8 green, 6 blue; 5 blue; 4 red, 13 green"
+        );
+    }
+}
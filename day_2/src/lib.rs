@@ -0,0 +1,4 @@
+pub mod follow;
+pub mod game;
+pub mod query;
+pub mod rules;
@@ -0,0 +1,14 @@
+use std::{error::Error, fs::read_to_string};
+
+use day_2::game::parse_games;
+
+const INPUT_FILE: &str = "input.txt";
+
+/// Prints the games in the canonical form: colours go as "red, green, blue" and
+/// the separators are surrounded by the single spaces
+fn main() -> Result<(), Box<dyn Error>> {
+    for game in parse_games(&read_to_string(INPUT_FILE)?)? {
+        println!("{}", game.normalized());
+    }
+    Ok(())
+}
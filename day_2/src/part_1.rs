@@ -1,38 +1,113 @@
-use std::{collections::HashMap, fs::read_to_string, sync::OnceLock};
+use std::{
+    env,
+    error::Error,
+    fs::{read_to_string, File},
+    io::{self, BufRead, BufReader},
+    time::Duration,
+};
+
+use day_2::{
+    follow::follow_games,
+    game::parse_games,
+    query::{filter_games, Query},
+    rules::{cube_constraints, RuleSet},
+};
 
 const INPUT_FILE: &str = "input.txt";
+const STDIN_SOURCE: &str = "-";
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Parses the games as their lines arrive and prints the updated possible game ids sum
+/// and power sum after each of them matching the `query`.
+///
+/// Stdin is read till the end, a file is tailed forever since more lines may be appended.
+fn follow(source: &str, rule_set: RuleSet, query: Option<&Query>) -> Result<(), Box<dyn Error>> {
+    let (reader, poll_interval): (Box<dyn BufRead>, _) = if source == STDIN_SOURCE {
+        (Box::new(io::stdin().lock()), None)
+    } else {
+        (
+            Box::new(BufReader::new(File::open(source)?)),
+            Some(FOLLOW_POLL_INTERVAL),
+        )
+    };
+
+    let (mut possible_game_ids_sum, mut power_sum) = (0, 0);
+    follow_games(reader, poll_interval, |line, game| match game {
+        Ok(game) if query.is_none_or(|query| query.matches(&game)) => {
+            if rule_set.is_possible(&game, cube_constraints()) {
+                possible_game_ids_sum += game.id;
+            }
+            power_sum += game.power();
+            println!("{possible_game_ids_sum} {power_sum}");
+        }
+        Ok(_) => {}
+        Err(err) => eprintln!("Unable to parse \"{line}\": {err:?}"),
+    })?;
 
-fn cube_constraints() -> &'static HashMap<&'static str, u64> {
-    static CUBE_CONSTRAINTS: OnceLock<HashMap<&'static str, u64>> = OnceLock::new();
-    CUBE_CONSTRAINTS.get_or_init(|| HashMap::from([("red", 12), ("green", 13), ("blue", 14)]))
+    Ok(())
 }
 
-fn main() {
-    let possible_game_ids_sum =
-        read_to_string(INPUT_FILE)
-            .unwrap()
-            .lines()
-            .fold(0, |possible_game_ids_sum, line| {
-                // Get rid of word "Game"
-                let line = &line[5..];
-                let (game_id, subsets) = line
-                    .split_once(": ")
-                    .map(|(game_id, line)| (game_id.parse::<u64>().unwrap(), line.split("; ")))
-                    .unwrap();
-                for subset in subsets {
-                    let cube_infos = subset
-                        .split(", ")
-                        .map(|info| info.split_once(' ').unwrap())
-                        .map(|(quantity, color)| (quantity.parse::<u64>().unwrap(), color));
-                    for (quantity, color) in cube_infos {
-                        // Limit is exceeded -> game is not possible
-                        if quantity > cube_constraints()[color] {
-                            return possible_game_ids_sum;
-                        }
-                    }
-                }
-
-                possible_game_ids_sum + game_id
-            });
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut query: Option<Query> = None;
+    let mut rule_set: Option<RuleSet> = None;
+    let mut follow_source: Option<String> = None;
+
+    let mut args = env::args().skip(1).peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--query" => {
+                query = Some(
+                    args.next()
+                        .ok_or("--query expects an expression")?
+                        .parse()?,
+                );
+            }
+            "--rules" => {
+                rule_set = Some(
+                    args.next()
+                        .ok_or("--rules expects \"replacement\" or \"cumulative\"")?
+                        .parse()?,
+                );
+            }
+            "--follow" => {
+                // Source is optional, the input file is followed by default
+                follow_source = Some(
+                    args.next_if(|arg| !arg.starts_with("--"))
+                        .unwrap_or_else(|| INPUT_FILE.to_owned()),
+                );
+            }
+            _ => return Err(format!("unknown argument \"{arg}\"").into()),
+        }
+    }
+
+    if let Some(source) = follow_source {
+        return follow(&source, rule_set.unwrap_or_default(), query.as_ref());
+    }
+
+    let games = parse_games(&read_to_string(INPUT_FILE)?)?;
+
+    if let Some(query) = query {
+        if rule_set.is_some() {
+            // Matching games are listed regardless of whether they're possible
+            return Err("--rules has no effect on --query without --follow".into());
+        }
+        // Prints the matching game ids and their sum
+        let ids: Vec<u64> = filter_games(&games, &query).map(|game| game.id).collect();
+        println!(
+            "{}",
+            ids.iter().map(u64::to_string).collect::<Vec<_>>().join(" ")
+        );
+        println!("{}", ids.iter().sum::<u64>());
+        return Ok(());
+    }
+
+    let rule_set = rule_set.unwrap_or_default();
+    let possible_game_ids_sum: u64 = games
+        .iter()
+        .filter(|game| rule_set.is_possible(game, cube_constraints()))
+        .map(|game| game.id)
+        .sum();
     println!("{possible_game_ids_sum}");
+
+    Ok(())
 }
@@ -0,0 +1,506 @@
+//! Filter expressions over the games
+//!
+//! ```text
+//! expression  := conjunction ("or" conjunction)*
+//! conjunction := negation ("and" negation)*
+//! negation    := "not" negation | "(" expression ")" | quantifier | comparison
+//! quantifier  := ("any" | "all" | "none") "(" expression ")"
+//! comparison  := value ("<" | "<=" | ">" | ">=" | "==" | "!=") value
+//! value       := number | colour | "total" | "id" | "draws"
+//!              | ("max" | "min" | "sum") "(" colour ")"
+//!              | "count" "(" expression ")"
+//! ```
+//!
+//! Quantifiers and `count` evaluate their expression for every draw of a game. Inside of them
+//! a colour stands for the quantity of the cubes of that colour in the current draw (`0` if the
+//! colour wasn't revealed) and `total` for the quantity of all the revealed cubes. On the game
+//! level colours can be used only through the aggregations over the draws, `draws` is the
+//! quantity of the draws and `id` is the game id.
+//!
+//! For instance, games where red never exceeds 5 but some draw has more than 10 blue cubes:
+//! `max(red) <= 5 and any(blue > 10)`
+
+use std::{error::Error, fmt, str::FromStr};
+
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{alpha1, char, digit1, multispace0, satisfy},
+    combinator::{all_consuming, cut, map, map_res, not, peek, value, verify},
+    multi::fold_many0,
+    sequence::{delimited, pair, preceded, terminated, tuple},
+    IResult,
+};
+
+use crate::game::{Draw, Game};
+
+const KEYWORDS: [&str; 13] = [
+    "and", "or", "not", "any", "all", "none", "count", "max", "min", "sum", "total", "id", "draws",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Quantifier {
+    Any,
+    All,
+    None,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Aggregation {
+    Max,
+    Min,
+    /// Saturated at `u64::MAX`
+    Sum,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Value {
+    Number(u64),
+    /// Quantity of the cubes of the colour in the current draw
+    Color(String),
+    /// Quantity of all the cubes in the current draw
+    Total,
+    Id,
+    Draws,
+    Aggregate(Aggregation, String),
+    /// Quantity of the draws satisfying the query
+    Count(Box<Query>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Query {
+    Or(Box<Query>, Box<Query>),
+    And(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+    Quantified(Quantifier, Box<Query>),
+    Compare(Value, Comparison, Value),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum QueryError {
+    /// Holds the part of the expression which couldn't be parsed
+    Syntax(String),
+    /// Draw-level value is used outside of a quantifier or `count`
+    OutsideDraw(String),
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryError::Syntax(rest) => write!(f, "unable to parse the query at \"{rest}\""),
+            QueryError::OutsideDraw(name) => write!(
+                f,
+                "\"{name}\" refers to a draw, use it inside of any/all/none/count \
+                 or aggregate it with max/min/sum"
+            ),
+        }
+    }
+}
+
+impl Error for QueryError {}
+
+impl Comparison {
+    fn apply(self, lhs: u64, rhs: u64) -> bool {
+        match self {
+            Comparison::Less => lhs < rhs,
+            Comparison::LessOrEqual => lhs <= rhs,
+            Comparison::Greater => lhs > rhs,
+            Comparison::GreaterOrEqual => lhs >= rhs,
+            Comparison::Equal => lhs == rhs,
+            Comparison::NotEqual => lhs != rhs,
+        }
+    }
+}
+
+impl Value {
+    fn check(&self, in_draw: bool) -> Result<(), QueryError> {
+        match self {
+            Value::Color(color) if !in_draw => Err(QueryError::OutsideDraw(color.clone())),
+            Value::Total if !in_draw => Err(QueryError::OutsideDraw("total".to_owned())),
+            Value::Count(query) => query.check(true),
+            _ => Ok(()),
+        }
+    }
+
+    fn evaluate(&self, game: &Game, draw: Option<&Draw>) -> u64 {
+        match self {
+            Value::Number(number) => *number,
+            // Presence of the draw is guaranteed by `Query::check`
+            Value::Color(color) => draw.map_or(0, |draw| draw.quantity(color)),
+            Value::Total => draw.map_or(0, Draw::total),
+            Value::Id => game.id,
+            Value::Draws => game.draws.len() as u64,
+            Value::Aggregate(aggregation, color) => {
+                let quantities = game.draws.iter().map(|draw| draw.quantity(color));
+                match aggregation {
+                    Aggregation::Max => quantities.max().unwrap_or_default(),
+                    Aggregation::Min => quantities.min().unwrap_or_default(),
+                    Aggregation::Sum => quantities.fold(0, u64::saturating_add),
+                }
+            }
+            Value::Count(query) => game
+                .draws
+                .iter()
+                .filter(|draw| query.evaluate(game, Some(draw)))
+                .count() as u64,
+        }
+    }
+}
+
+impl Query {
+    /// Ensures that the draw-level values are used only inside of the quantifiers
+    fn check(&self, in_draw: bool) -> Result<(), QueryError> {
+        match self {
+            Query::Or(lhs, rhs) | Query::And(lhs, rhs) => {
+                lhs.check(in_draw)?;
+                rhs.check(in_draw)
+            }
+            Query::Not(query) => query.check(in_draw),
+            Query::Quantified(_, query) => query.check(true),
+            Query::Compare(lhs, _, rhs) => {
+                lhs.check(in_draw)?;
+                rhs.check(in_draw)
+            }
+        }
+    }
+
+    fn evaluate(&self, game: &Game, draw: Option<&Draw>) -> bool {
+        match self {
+            Query::Or(lhs, rhs) => lhs.evaluate(game, draw) || rhs.evaluate(game, draw),
+            Query::And(lhs, rhs) => lhs.evaluate(game, draw) && rhs.evaluate(game, draw),
+            Query::Not(query) => !query.evaluate(game, draw),
+            Query::Quantified(quantifier, query) => {
+                let mut draws = game.draws.iter();
+                match quantifier {
+                    Quantifier::Any => draws.any(|draw| query.evaluate(game, Some(draw))),
+                    Quantifier::All => draws.all(|draw| query.evaluate(game, Some(draw))),
+                    Quantifier::None => !draws.any(|draw| query.evaluate(game, Some(draw))),
+                }
+            }
+            Query::Compare(lhs, comparison, rhs) => {
+                comparison.apply(lhs.evaluate(game, draw), rhs.evaluate(game, draw))
+            }
+        }
+    }
+
+    pub fn matches(&self, game: &Game) -> bool {
+        self.evaluate(game, None)
+    }
+}
+
+impl FromStr for Query {
+    type Err = QueryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let query = all_consuming(parse_expression)(s)
+            .map(|(_, query)| query)
+            .map_err(|err| match err {
+                nom::Err::Error(err) | nom::Err::Failure(err) => {
+                    QueryError::Syntax(err.input.to_owned())
+                }
+                nom::Err::Incomplete(_) => QueryError::Syntax(String::new()),
+            })?;
+        query.check(false)?;
+        Ok(query)
+    }
+}
+
+/// Returns the games matching the `query`
+pub fn filter_games<'a>(
+    games: &'a [Game],
+    query: &'a Query,
+) -> impl Iterator<Item = &'a Game> + 'a {
+    games.iter().filter(|game| query.matches(game))
+}
+
+fn ws<'a, O>(
+    inner: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O> {
+    delimited(multispace0, inner, multispace0)
+}
+
+fn keyword<'a>(word: &'static str) -> impl FnMut(&'a str) -> IResult<&'a str, &'a str> {
+    ws(terminated(
+        tag(word),
+        not(peek(satisfy(|c| c.is_alphanumeric() || c == '_'))),
+    ))
+}
+
+fn parens<'a, O>(
+    inner: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O> {
+    delimited(ws(char('(')), inner, ws(char(')')))
+}
+
+fn parse_color(input: &str) -> IResult<&str, String> {
+    map(
+        ws(verify(alpha1, |word: &str| !KEYWORDS.contains(&word))),
+        str::to_owned,
+    )(input)
+}
+
+fn parse_value(input: &str) -> IResult<&str, Value> {
+    alt((
+        map(
+            ws(map_res(digit1, |s: &str| s.parse::<u64>())),
+            Value::Number,
+        ),
+        map(
+            preceded(keyword("count"), cut(parens(parse_expression))),
+            |query| Value::Count(Box::new(query)),
+        ),
+        map(
+            pair(
+                alt((
+                    value(Aggregation::Max, keyword("max")),
+                    value(Aggregation::Min, keyword("min")),
+                    value(Aggregation::Sum, keyword("sum")),
+                )),
+                cut(parens(parse_color)),
+            ),
+            |(aggregation, color)| Value::Aggregate(aggregation, color),
+        ),
+        value(Value::Total, keyword("total")),
+        value(Value::Id, keyword("id")),
+        value(Value::Draws, keyword("draws")),
+        map(parse_color, Value::Color),
+    ))(input)
+}
+
+fn parse_comparison(input: &str) -> IResult<&str, Query> {
+    map(
+        tuple((
+            parse_value,
+            ws(alt((
+                value(Comparison::LessOrEqual, tag("<=")),
+                value(Comparison::GreaterOrEqual, tag(">=")),
+                value(Comparison::Equal, tag("==")),
+                value(Comparison::NotEqual, tag("!=")),
+                value(Comparison::Less, tag("<")),
+                value(Comparison::Greater, tag(">")),
+            ))),
+            parse_value,
+        )),
+        |(lhs, comparison, rhs)| Query::Compare(lhs, comparison, rhs),
+    )(input)
+}
+
+fn parse_quantified(input: &str) -> IResult<&str, Query> {
+    map(
+        pair(
+            alt((
+                value(Quantifier::Any, keyword("any")),
+                value(Quantifier::All, keyword("all")),
+                value(Quantifier::None, keyword("none")),
+            )),
+            cut(parens(parse_expression)),
+        ),
+        |(quantifier, query)| Query::Quantified(quantifier, Box::new(query)),
+    )(input)
+}
+
+fn parse_negation(input: &str) -> IResult<&str, Query> {
+    alt((
+        map(preceded(keyword("not"), parse_negation), |query| {
+            Query::Not(Box::new(query))
+        }),
+        parens(parse_expression),
+        parse_quantified,
+        parse_comparison,
+    ))(input)
+}
+
+fn parse_conjunction(input: &str) -> IResult<&str, Query> {
+    let (input, first) = parse_negation(input)?;
+    fold_many0(
+        preceded(keyword("and"), parse_negation),
+        move || first.clone(),
+        |lhs, rhs| Query::And(Box::new(lhs), Box::new(rhs)),
+    )(input)
+}
+
+fn parse_expression(input: &str) -> IResult<&str, Query> {
+    let (input, first) = parse_conjunction(input)?;
+    fold_many0(
+        preceded(keyword("or"), parse_conjunction),
+        move || first.clone(),
+        |lhs, rhs| Query::Or(Box::new(lhs), Box::new(rhs)),
+    )(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAMES: [&str; 6] = [
+        "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green",
+        "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue",
+        "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red",
+        "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red",
+        "Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green",
+        "Game 6: 2 red, 12 blue",
+    ];
+
+    /// Ids of the games matching the query
+    fn ids(query: &str) -> Vec<u64> {
+        let games: Vec<Game> = GAMES.iter().map(|game| game.parse().unwrap()).collect();
+        let query: Query = query.parse().unwrap();
+        filter_games(&games, &query).map(|game| game.id).collect()
+    }
+
+    fn compare(lhs: Value, comparison: Comparison, rhs: Value) -> Query {
+        Query::Compare(lhs, comparison, rhs)
+    }
+
+    fn id_is(id: u64) -> Query {
+        compare(Value::Id, Comparison::Equal, Value::Number(id))
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert_eq!(
+            "id == 1 or id == 2 and id == 3".parse(),
+            Ok(Query::Or(
+                Box::new(id_is(1)),
+                Box::new(Query::And(Box::new(id_is(2)), Box::new(id_is(3))))
+            ))
+        );
+        assert_eq!(ids("id == 1 or id == 2 and id == 3"), vec![1]);
+        assert_eq!(ids("(id == 1 or id == 2) and id == 2"), vec![2]);
+    }
+
+    #[test]
+    fn not_applies_to_the_next_negation() {
+        assert_eq!(ids("not id == 1"), vec![2, 3, 4, 5, 6]);
+        assert_eq!(ids("not not id == 1"), vec![1]);
+        assert_eq!(ids("not id == 1 and id <= 2"), vec![2]);
+        assert_eq!(ids("not (id == 1 or id == 2)"), vec![3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn quantifiers_look_at_every_draw() {
+        assert_eq!(ids("any(red > 10)"), vec![3, 4]);
+        assert_eq!(ids("all(blue > 0)"), vec![2, 5, 6]);
+        assert_eq!(ids("none(green > 5)"), vec![1, 2, 4, 5, 6]);
+        assert_eq!(ids("any(total >= 20)"), vec![3, 4]);
+    }
+
+    #[test]
+    fn aggregations_combine_the_draws() {
+        assert_eq!(ids("max(red) <= 5"), vec![1, 2, 6]);
+        assert_eq!(ids("min(red) == 0"), vec![1, 2]);
+        assert_eq!(ids("sum(blue) > 10"), vec![3, 4, 6]);
+        assert_eq!(ids("count(green > 2) >= 2"), vec![3, 4]);
+        assert_eq!(ids("draws == 2"), vec![5]);
+        assert_eq!(ids("count(red > 0) == draws"), vec![3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn sums_saturate() {
+        let games: Vec<Game> = [
+            "Game 1: 18446744073709551615 red; 1 red",
+            "Game 2: 18446744073709551615 red, 1 blue",
+        ]
+        .iter()
+        .map(|game| game.parse().unwrap())
+        .collect();
+        for (query, ids) in [
+            ("sum(red) > 0", vec![1, 2]),
+            ("sum(red) == 18446744073709551615", vec![1, 2]),
+            ("any(total == 18446744073709551615)", vec![1, 2]),
+        ] {
+            let query: Query = query.parse().unwrap();
+            let matching: Vec<u64> = filter_games(&games, &query).map(|game| game.id).collect();
+            assert_eq!(matching, ids);
+        }
+    }
+
+    #[test]
+    fn documented_example() {
+        assert_eq!(ids("max(red) <= 5 and any(blue > 10)"), vec![6]);
+    }
+
+    #[test]
+    fn keywords_end_at_word_boundaries() {
+        assert_eq!(
+            "any(anything > 0)".parse(),
+            Ok(Query::Quantified(
+                Quantifier::Any,
+                Box::new(compare(
+                    Value::Color("anything".to_owned()),
+                    Comparison::Greater,
+                    Value::Number(0)
+                ))
+            ))
+        );
+        assert_eq!(
+            "any(orange > 0 or red > 10)".parse(),
+            Ok(Query::Quantified(
+                Quantifier::Any,
+                Box::new(Query::Or(
+                    Box::new(compare(
+                        Value::Color("orange".to_owned()),
+                        Comparison::Greater,
+                        Value::Number(0)
+                    )),
+                    Box::new(compare(
+                        Value::Color("red".to_owned()),
+                        Comparison::Greater,
+                        Value::Number(10)
+                    ))
+                ))
+            ))
+        );
+        assert_eq!(ids("any(orange > 0 or red > 10)"), vec![3, 4]);
+        assert_eq!(ids("max(orange) == 0"), vec![1, 2, 3, 4, 5, 6]);
+        assert!(matches!(
+            "id == 1 orange".parse::<Query>(),
+            Err(QueryError::Syntax(_))
+        ));
+    }
+
+    #[test]
+    fn draw_values_are_rejected_outside_of_draws() {
+        assert_eq!(
+            "red > 1".parse::<Query>(),
+            Err(QueryError::OutsideDraw("red".to_owned()))
+        );
+        assert_eq!(
+            "id == 1 or total > 1".parse::<Query>(),
+            Err(QueryError::OutsideDraw("total".to_owned()))
+        );
+        assert_eq!(
+            "anything > 0".parse::<Query>(),
+            Err(QueryError::OutsideDraw("anything".to_owned()))
+        );
+        assert!("count(red > 1) > 0".parse::<Query>().is_ok());
+    }
+
+    #[test]
+    fn syntax_errors_are_reported() {
+        for query in [
+            "",
+            "id ==",
+            "id = 1",
+            "any(red > 1",
+            "max(1) > 0",
+            "id == 1 and",
+            "any red > 1",
+        ] {
+            assert!(
+                matches!(query.parse::<Query>(), Err(QueryError::Syntax(_))),
+                "{query}"
+            );
+        }
+    }
+}
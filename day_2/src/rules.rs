@@ -0,0 +1,110 @@
+use std::{collections::HashMap, str::FromStr, sync::OnceLock};
+
+use crate::game::Game;
+
+pub fn cube_constraints() -> &'static HashMap<&'static str, u64> {
+    static CUBE_CONSTRAINTS: OnceLock<HashMap<&'static str, u64>> = OnceLock::new();
+    CUBE_CONSTRAINTS.get_or_init(|| HashMap::from([("red", 12), ("green", 13), ("blue", 14)]))
+}
+
+/// Rules which decide whether a game is possible with the bag of cubes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RuleSet {
+    /// Cubes are put back into the bag after each draw, so only the per-draw maximum matters
+    #[default]
+    WithReplacement,
+    /// Cubes aren't returned, so the running totals of the draws must fit into the bag
+    Cumulative,
+}
+
+impl RuleSet {
+    pub fn is_possible(self, game: &Game, constraints: &HashMap<&str, u64>) -> bool {
+        // Unknown colour gets zero limit
+        let limit = |color: &str| constraints.get(color).copied().unwrap_or(0);
+
+        match self {
+            RuleSet::WithReplacement => game.draws.iter().all(|draw| {
+                draw.0
+                    .iter()
+                    .all(|cubes| cubes.quantity <= limit(&cubes.color))
+            }),
+            RuleSet::Cumulative => {
+                let mut taken: HashMap<&str, u64> = HashMap::new();
+                game.draws.iter().all(|draw| {
+                    draw.0.iter().all(|cubes| {
+                        let taken = taken.entry(&cubes.color).or_default();
+                        // Running total beyond `u64` can't fit into any bag
+                        match taken.checked_add(cubes.quantity) {
+                            Some(total) => {
+                                *taken = total;
+                                total <= limit(&cubes.color)
+                            }
+                            None => false,
+                        }
+                    })
+                })
+            }
+        }
+    }
+}
+
+impl FromStr for RuleSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "replacement" => Ok(RuleSet::WithReplacement),
+            "cumulative" => Ok(RuleSet::Cumulative),
+            _ => Err(format!(
+                "unknown rule set \"{s}\", expected \"replacement\" or \"cumulative\""
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_possible(rule_set: RuleSet, game: &str) -> bool {
+        rule_set.is_possible(&game.parse().unwrap(), cube_constraints())
+    }
+
+    #[test]
+    fn cumulative_rules_sum_up_the_draws() {
+        let game = "Game 1: 8 red, 2 blue; 5 red; 14 blue";
+        assert!(is_possible(RuleSet::WithReplacement, game));
+        assert!(!is_possible(RuleSet::Cumulative, game));
+
+        let game = "Game 2: 6 red; 6 red, 13 green; 14 blue";
+        assert!(is_possible(RuleSet::WithReplacement, game));
+        assert!(is_possible(RuleSet::Cumulative, game));
+    }
+
+    #[test]
+    fn unknown_colour_is_never_possible() {
+        for rule_set in [RuleSet::WithReplacement, RuleSet::Cumulative] {
+            assert!(!is_possible(rule_set, "Game 1: 1 red, 1 orange"));
+        }
+    }
+
+    #[test]
+    fn overflowing_total_is_impossible() {
+        let game = "Game 1: 1 red; 18446744073709551615 red";
+        assert!(!is_possible(RuleSet::WithReplacement, game));
+        assert!(!is_possible(RuleSet::Cumulative, game));
+    }
+
+    #[test]
+    fn rule_sets_are_parsed() {
+        assert_eq!("replacement".parse(), Ok(RuleSet::WithReplacement));
+        assert_eq!("cumulative".parse(), Ok(RuleSet::Cumulative));
+        assert_eq!(
+            "greedy".parse::<RuleSet>(),
+            Err(
+                "unknown rule set \"greedy\", expected \"replacement\" or \"cumulative\""
+                    .to_owned()
+            )
+        );
+    }
+}
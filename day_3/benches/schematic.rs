@@ -0,0 +1,104 @@
+//! Compares the cell-indexed lookup of the adjacent numbers with the linear scan over all of
+//! them on large synthetic schematics
+
+use std::ops::RangeInclusive;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use day_3::schematic::Schematic;
+
+const SIZES: [usize; 3] = [70, 140, 280];
+const SYMBOLS: [char; 6] = ['*', '#', '+', '$', '/', '@'];
+
+/// Schematic resembling the puzzle input: short numbers and sparse symbols, generated with
+/// a linear congruential generator to stay reproducible
+fn synthetic_schematic(size: usize) -> String {
+    let mut state: u64 = 0x2023_1203;
+    let mut next = move |bound: u64| {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (state >> 33) % bound
+    };
+
+    let mut schematic = String::with_capacity(size * (size + 1));
+    for _ in 0..size {
+        let mut line = String::with_capacity(size);
+        while line.len() < size {
+            match next(10) {
+                0..=1 => {
+                    let digits = 1 + next(3) as usize;
+                    for _ in 0..digits.min(size - line.len()) {
+                        line.push(char::from_digit(next(10) as u32, 10).unwrap());
+                    }
+                    if line.len() < size {
+                        line.push('.');
+                    }
+                }
+                2 => line.push(SYMBOLS[next(SYMBOLS.len() as u64) as usize]),
+                _ => line.push('.'),
+            }
+        }
+        schematic.push_str(&line);
+        schematic.push('\n');
+    }
+    schematic
+}
+
+/// `((is_used, number), (line, columns))`
+type LinearNumber = ((bool, u64), (usize, RangeInclusive<usize>));
+
+/// Previous approach: every neighbour cell of every symbol is searched among all the numbers
+fn linear_scan_part_numbers_sum(numbers: &mut [LinearNumber], symbols: &[(usize, usize)]) -> u64 {
+    let mut part_numbers_sum = 0;
+    for &(i, j) in symbols {
+        for (line, column) in (i.saturating_sub(1)..=i + 1)
+            .flat_map(|line| (j.saturating_sub(1)..=j + 1).map(move |column| (line, column)))
+        {
+            if let Some(((is_used, number), _)) = numbers
+                .iter_mut()
+                .filter(|((is_used, _), _)| !is_used)
+                .find(|(_, (i, js))| line == *i && js.contains(&column))
+            {
+                *is_used = true;
+                part_numbers_sum += *number;
+            }
+        }
+    }
+    part_numbers_sum
+}
+
+fn part_numbers_sum(c: &mut Criterion) {
+    let mut group = c.benchmark_group("part_numbers_sum");
+    group.sample_size(10);
+
+    for size in SIZES {
+        let schematic: Schematic = synthetic_schematic(size).parse().unwrap();
+        let numbers: Vec<_> = schematic
+            .numbers()
+            .iter()
+            .map(|number| ((false, number.value), (number.line, number.columns.clone())))
+            .collect();
+        let symbols: Vec<_> = schematic
+            .symbols()
+            .iter()
+            .map(|(position, _)| *position)
+            .collect();
+
+        assert_eq!(
+            linear_scan_part_numbers_sum(&mut numbers.clone(), &symbols),
+            schematic.part_numbers_sum()
+        );
+
+        group.bench_with_input(BenchmarkId::new("linear_scan", size), &size, |b, _| {
+            b.iter(|| linear_scan_part_numbers_sum(&mut numbers.clone(), black_box(&symbols)))
+        });
+        group.bench_with_input(BenchmarkId::new("cell_indexed", size), &size, |b, _| {
+            b.iter(|| black_box(&schematic).part_numbers_sum())
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, part_numbers_sum);
+criterion_main!(benches);
@@ -0,0 +1,352 @@
+//! Bipartite graph of the numbers and the symbols adjacent to them
+
+use std::fmt::Write;
+
+use grid::Position;
+
+use crate::schematic::{Number, NumberId, Schematic, GEAR_SYMBOL};
+
+/// Index of a symbol in the `AdjacencyGraph::symbols`
+pub type SymbolId = usize;
+
+/// Connected component of the graph containing at least one symbol
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Machine {
+    pub symbols: Vec<SymbolId>,
+    pub numbers: Vec<NumberId>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Node {
+    Symbol(SymbolId),
+    Number(NumberId),
+}
+
+#[derive(Clone, Debug)]
+pub struct AdjacencyGraph {
+    /// Symbols in the line-by-line order
+    symbols: Vec<(Position, char)>,
+    numbers: Vec<Number>,
+    symbol_numbers: Vec<Vec<NumberId>>,
+    number_symbols: Vec<Vec<SymbolId>>,
+}
+
+impl AdjacencyGraph {
+    pub fn new(schematic: &Schematic) -> Self {
+        let symbols = schematic.symbols().to_vec();
+        let symbol_numbers: Vec<Vec<NumberId>> = symbols
+            .iter()
+            .map(|(position, _)| schematic.adjacent_numbers(*position))
+            .collect();
+
+        let mut number_symbols = vec![Vec::new(); schematic.numbers().len()];
+        for (symbol_id, numbers) in symbol_numbers.iter().enumerate() {
+            for number_id in numbers {
+                number_symbols[*number_id].push(symbol_id);
+            }
+        }
+
+        AdjacencyGraph {
+            symbols,
+            numbers: schematic.numbers().to_vec(),
+            symbol_numbers,
+            number_symbols,
+        }
+    }
+
+    pub fn symbols(&self) -> &[(Position, char)] {
+        &self.symbols
+    }
+
+    pub fn numbers(&self) -> &[Number] {
+        &self.numbers
+    }
+
+    pub fn symbol_at(&self, position: Position) -> Option<SymbolId> {
+        self.symbols
+            .binary_search_by_key(&position, |(position, _)| *position)
+            .ok()
+    }
+
+    /// Numbers adjacent to the symbol in the line-by-line order
+    pub fn symbol_neighbors(&self, id: SymbolId) -> &[NumberId] {
+        &self.symbol_numbers[id]
+    }
+
+    /// Symbols adjacent to the number in the line-by-line order
+    pub fn number_neighbors(&self, id: NumberId) -> &[SymbolId] {
+        &self.number_symbols[id]
+    }
+
+    /// Numbers touching no symbol
+    pub fn isolated_numbers(&self) -> Vec<NumberId> {
+        (0..self.numbers.len())
+            .filter(|id| self.number_symbols[*id].is_empty())
+            .collect()
+    }
+
+    /// Connected components of the graph, isolated numbers don't form a machine
+    pub fn machines(&self) -> Vec<Machine> {
+        let mut visited_symbols = vec![false; self.symbols.len()];
+        let mut visited_numbers = vec![false; self.numbers.len()];
+        let mut machines = Vec::new();
+
+        for start in 0..self.symbols.len() {
+            if visited_symbols[start] {
+                continue;
+            }
+            visited_symbols[start] = true;
+
+            let mut machine = Machine::default();
+            let mut pending = vec![Node::Symbol(start)];
+            while let Some(node) = pending.pop() {
+                match node {
+                    Node::Symbol(id) => {
+                        machine.symbols.push(id);
+                        for &number_id in &self.symbol_numbers[id] {
+                            if !visited_numbers[number_id] {
+                                visited_numbers[number_id] = true;
+                                pending.push(Node::Number(number_id));
+                            }
+                        }
+                    }
+                    Node::Number(id) => {
+                        machine.numbers.push(id);
+                        for &symbol_id in &self.number_symbols[id] {
+                            if !visited_symbols[symbol_id] {
+                                visited_symbols[symbol_id] = true;
+                                pending.push(Node::Symbol(symbol_id));
+                            }
+                        }
+                    }
+                }
+            }
+            machine.symbols.sort_unstable();
+            machine.numbers.sort_unstable();
+            machines.push(machine);
+        }
+
+        machines
+    }
+
+    /// Sum of the numbers adjacent to any symbol
+    pub fn part_numbers_sum(&self) -> u64 {
+        self.numbers
+            .iter()
+            .zip(&self.number_symbols)
+            .filter(|(_, symbols)| !symbols.is_empty())
+            .map(|(number, _)| number.value)
+            .sum()
+    }
+
+    /// Sum of the products of the numbers adjacent to the gears having exactly two of them
+    pub fn gear_ratios_sum(&self) -> u64 {
+        self.symbols
+            .iter()
+            .zip(&self.symbol_numbers)
+            .filter(|((_, symbol), numbers)| *symbol == GEAR_SYMBOL && numbers.len() == 2)
+            .map(|(_, numbers)| {
+                numbers
+                    .iter()
+                    .map(|id| self.numbers[*id].value)
+                    .product::<u64>()
+            })
+            .sum()
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{\"symbols\":[");
+        for (id, ((i, j), symbol)) in self.symbols.iter().enumerate() {
+            if id > 0 {
+                json.push(',');
+            }
+            write!(
+                json,
+                "{{\"id\":{id},\"symbol\":\"{}\",\"line\":{i},\"column\":{j}}}",
+                escape(*symbol)
+            )
+            .unwrap();
+        }
+        json.push_str("],\"numbers\":[");
+        for (id, number) in self.numbers.iter().enumerate() {
+            if id > 0 {
+                json.push(',');
+            }
+            write!(
+                json,
+                "{{\"id\":{id},\"value\":{},\"line\":{},\"columns\":[{},{}]}}",
+                number.value,
+                number.line,
+                number.columns.start(),
+                number.columns.end()
+            )
+            .unwrap();
+        }
+        json.push_str("],\"edges\":[");
+        for (k, (symbol_id, number_id)) in self.edges().enumerate() {
+            if k > 0 {
+                json.push(',');
+            }
+            write!(json, "{{\"symbol\":{symbol_id},\"number\":{number_id}}}").unwrap();
+        }
+        json.push_str("]}");
+        json
+    }
+
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("graph schematic {\n");
+        for (id, (_, symbol)) in self.symbols.iter().enumerate() {
+            writeln!(dot, "    s{id} [label=\"{}\", shape=box];", escape(*symbol)).unwrap();
+        }
+        for (id, number) in self.numbers.iter().enumerate() {
+            writeln!(dot, "    n{id} [label=\"{}\"];", number.value).unwrap();
+        }
+        for (symbol_id, number_id) in self.edges() {
+            writeln!(dot, "    s{symbol_id} -- n{number_id};").unwrap();
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn edges(&self) -> impl Iterator<Item = (SymbolId, NumberId)> + '_ {
+        self.symbol_numbers
+            .iter()
+            .enumerate()
+            .flat_map(|(symbol_id, numbers)| numbers.iter().map(move |id| (symbol_id, *id)))
+    }
+}
+
+/// Escapes a symbol for the JSON and DOT string literals, control characters are written
+/// as `\u00XX`
+fn escape(symbol: char) -> String {
+    match symbol {
+        '"' | '\\' => format!("\\{symbol}"),
+        _ if symbol.is_control() => format!("\\u{:04x}", symbol as u32),
+        _ => symbol.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schematic::SAMPLE;
+
+    fn graph() -> AdjacencyGraph {
+        AdjacencyGraph::new(&SAMPLE.parse().unwrap())
+    }
+
+    fn values(graph: &AdjacencyGraph, ids: &[NumberId]) -> Vec<u64> {
+        ids.iter().map(|id| graph.numbers()[*id].value).collect()
+    }
+
+    #[test]
+    fn machines_are_connected_components() {
+        let graph = graph();
+        let machines: Vec<(Vec<char>, Vec<u64>)> = graph
+            .machines()
+            .iter()
+            .map(|machine| {
+                (
+                    machine
+                        .symbols
+                        .iter()
+                        .map(|id| graph.symbols()[*id].1)
+                        .collect(),
+                    values(&graph, &machine.numbers),
+                )
+            })
+            .collect();
+        assert_eq!(
+            machines,
+            vec![
+                (vec!['*'], vec![467, 35]),
+                (vec!['#'], vec![633]),
+                (vec!['*'], vec![617]),
+                (vec!['+'], vec![592]),
+                (vec!['$'], vec![664]),
+                (vec!['*'], vec![755, 598]),
+            ]
+        );
+        assert_eq!(values(&graph, &graph.isolated_numbers()), vec![114, 58]);
+    }
+
+    #[test]
+    fn shared_numbers_join_the_machines() {
+        let graph = AdjacencyGraph::new(&"*.#\n.5.\n..$".parse().unwrap());
+        assert_eq!(
+            graph.machines(),
+            vec![Machine {
+                symbols: vec![0, 1, 2],
+                numbers: vec![0]
+            }]
+        );
+        assert_eq!(graph.number_neighbors(0), &[0, 1, 2]);
+        assert!(graph.isolated_numbers().is_empty());
+    }
+
+    #[test]
+    fn neighbors_are_symmetric() {
+        let graph = graph();
+        for symbol_id in 0..graph.symbols().len() {
+            for number_id in graph.symbol_neighbors(symbol_id) {
+                assert!(graph.number_neighbors(*number_id).contains(&symbol_id));
+            }
+        }
+        let gear = graph.symbol_at((8, 5)).unwrap();
+        assert_eq!(values(&graph, graph.symbol_neighbors(gear)), vec![755, 598]);
+        let number = graph.numbers().iter().position(|n| n.value == 617).unwrap();
+        assert_eq!(
+            graph.number_neighbors(number),
+            &[graph.symbol_at((4, 3)).unwrap()]
+        );
+        assert_eq!(graph.symbol_at((0, 0)), None);
+    }
+
+    #[test]
+    fn answers_match_the_schematic() {
+        let schematic: Schematic = SAMPLE.parse().unwrap();
+        let graph = AdjacencyGraph::new(&schematic);
+        assert_eq!(graph.part_numbers_sum(), schematic.part_numbers_sum());
+        assert_eq!(graph.gear_ratios_sum(), schematic.gear_ratios_sum());
+        assert_eq!(
+            (graph.part_numbers_sum(), graph.gear_ratios_sum()),
+            (4361, 467835)
+        );
+    }
+
+    #[test]
+    fn exports_are_stable() {
+        let graph = AdjacencyGraph::new(&"12.\n.*3".parse().unwrap());
+        assert_eq!(
+            graph.to_json(),
+            "{\"symbols\":[{\"id\":0,\"symbol\":\"*\",\"line\":1,\"column\":1}],\
+             \"numbers\":[{\"id\":0,\"value\":12,\"line\":0,\"columns\":[0,1]},\
+             {\"id\":1,\"value\":3,\"line\":1,\"columns\":[2,2]}],\
+             \"edges\":[{\"symbol\":0,\"number\":0},{\"symbol\":0,\"number\":1}]}"
+        );
+        assert_eq!(
+            graph.to_dot(),
+            "\
+graph schematic {
+    s0 [label=\"*\", shape=box];
+    n0 [label=\"12\"];
+    n1 [label=\"3\"];
+    s0 -- n0;
+    s0 -- n1;
+}
+"
+        );
+    }
+
+    #[test]
+    fn symbols_are_escaped() {
+        assert_eq!(escape('*'), "*");
+        assert_eq!(escape('"'), "\\\"");
+        assert_eq!(escape('\\'), "\\\\");
+        assert_eq!(escape('\t'), "\\u0009");
+        assert_eq!(escape('\u{7f}'), "\\u007f");
+
+        let graph = AdjacencyGraph::new(&"1\t".parse().unwrap());
+        assert!(graph.to_json().contains("\"symbol\":\"\\u0009\""));
+    }
+}
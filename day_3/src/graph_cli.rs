@@ -0,0 +1,78 @@
+use std::{env, error::Error, fs::read_to_string};
+
+use day_3::{graph::AdjacencyGraph, schematic::Schematic};
+use grid::Position;
+
+const INPUT_FILE: &str = "input.txt";
+
+fn parse_position(arg: Option<String>) -> Result<Position, Box<dyn Error>> {
+    let arg = arg.ok_or("expected a LINE,COLUMN position")?;
+    let (i, j) = arg
+        .split_once(',')
+        .ok_or_else(|| format!("position \"{arg}\" doesn't match LINE,COLUMN"))?;
+    Ok((i.trim().parse()?, j.trim().parse()?))
+}
+
+/// Prints the part 1 and part 2 answers derived from the numbers and symbols graph,
+/// or answers one of the queries about it
+fn main() -> Result<(), Box<dyn Error>> {
+    let schematic: Schematic = read_to_string(INPUT_FILE)?.parse()?;
+    let graph = AdjacencyGraph::new(&schematic);
+
+    let mut args = env::args().skip(1);
+    let Some(arg) = args.next() else {
+        println!("{}", graph.part_numbers_sum());
+        println!("{}", graph.gear_ratios_sum());
+        return Ok(());
+    };
+
+    match arg.as_str() {
+        "--symbol" => {
+            let position = parse_position(args.next())?;
+            let id = graph
+                .symbol_at(position)
+                .ok_or_else(|| format!("there is no symbol at {position:?}"))?;
+            for number_id in graph.symbol_neighbors(id) {
+                println!("{}", graph.numbers()[*number_id].value);
+            }
+        }
+        "--number" => {
+            let position = parse_position(args.next())?;
+            let id = schematic
+                .number_at(position)
+                .ok_or_else(|| format!("there is no number at {position:?}"))?;
+            for symbol_id in graph.number_neighbors(id) {
+                let ((i, j), symbol) = graph.symbols()[*symbol_id];
+                println!("{i},{j} {symbol}");
+            }
+        }
+        "--machines" => {
+            for machine in graph.machines() {
+                let symbols: Vec<String> = machine
+                    .symbols
+                    .iter()
+                    .map(|id| graph.symbols()[*id].1.to_string())
+                    .collect();
+                let numbers: Vec<String> = machine
+                    .numbers
+                    .iter()
+                    .map(|id| graph.numbers()[*id].value.to_string())
+                    .collect();
+                println!("{} | {}", symbols.join(" "), numbers.join(" "));
+            }
+        }
+        "--isolated" => {
+            for id in graph.isolated_numbers() {
+                println!("{}", graph.numbers()[id].value);
+            }
+        }
+        "--export" => match args.next().as_deref() {
+            Some("json") => println!("{}", graph.to_json()),
+            Some("dot") => print!("{}", graph.to_dot()),
+            _ => return Err("--export expects \"json\" or \"dot\"".into()),
+        },
+        _ => return Err(format!("unknown argument \"{arg}\"").into()),
+    }
+
+    Ok(())
+}
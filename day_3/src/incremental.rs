@@ -0,0 +1,164 @@
+//! Schematic keeping the part numbers sum and the gear ratios sum up to date while its cells
+//! are edited one by one
+
+use grid::Position;
+
+use crate::schematic::{EditError, NumberId, Schematic, GEAR_SYMBOL};
+
+#[derive(Clone, Debug)]
+pub struct IncrementalSchematic {
+    schematic: Schematic,
+    part_numbers_sum: u64,
+    gear_ratios_sum: u64,
+}
+
+impl IncrementalSchematic {
+    pub fn new(schematic: Schematic) -> Self {
+        IncrementalSchematic {
+            part_numbers_sum: schematic.part_numbers_sum(),
+            gear_ratios_sum: schematic.gear_ratios_sum(),
+            schematic,
+        }
+    }
+
+    pub fn schematic(&self) -> &Schematic {
+        &self.schematic
+    }
+
+    pub fn part_numbers_sum(&self) -> u64 {
+        self.part_numbers_sum
+    }
+
+    pub fn gear_ratios_sum(&self) -> u64 {
+        self.gear_ratios_sum
+    }
+
+    /// Numbers occupying the cell or adjacent to it, only their part status can be changed by
+    /// editing the cell
+    fn numbers_around(&self, position: Position) -> Vec<NumberId> {
+        let mut ids = self.schematic.adjacent_numbers(position);
+        ids.extend(self.schematic.number_at(position));
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    fn part_numbers_around_sum(&self, position: Position) -> u64 {
+        self.numbers_around(position)
+            .into_iter()
+            .filter(|id| self.schematic.is_part_number(*id))
+            .map(|id| self.schematic.numbers()[id].value)
+            .sum()
+    }
+
+    /// Gears whose ratio may be changed by editing the cell: the cell itself and the gears
+    /// adjacent to the numbers around it. Numbers appearing after the edit consist of the cell
+    /// and the digits of those numbers, so the gears next to them are covered as well
+    fn gears_affected_by(&self, position: Position) -> Vec<Position> {
+        let cells = self.schematic.cells();
+        let mut gears: Vec<Position> = self
+            .numbers_around(position)
+            .into_iter()
+            .flat_map(|id| {
+                let number = &self.schematic.numbers()[id];
+                number
+                    .columns
+                    .clone()
+                    .map(move |column| (number.line, column))
+            })
+            .chain([position])
+            .flat_map(|cell| cells.neighbors8(cell).chain([cell]))
+            .filter(|cell| cells[*cell] == GEAR_SYMBOL)
+            .collect();
+        gears.push(position);
+        gears.sort_unstable();
+        gears.dedup();
+        gears
+    }
+
+    fn gear_ratios_of(&self, gears: &[Position]) -> u64 {
+        gears
+            .iter()
+            .filter_map(|gear| self.schematic.gear_ratio(*gear))
+            .sum()
+    }
+
+    /// Places a digit, a symbol or '.' into the cell and updates the sums, only the numbers
+    /// and the gears around the cell are reevaluated
+    pub fn set_cell(&mut self, position: Position, c: char) -> Result<(), EditError> {
+        if !self.schematic.cells().contains(position) {
+            return Err(EditError::OutOfBounds(position));
+        }
+
+        let gears = self.gears_affected_by(position);
+        let part_numbers_before = self.part_numbers_around_sum(position);
+        let gear_ratios_before = self.gear_ratios_of(&gears);
+
+        self.schematic.set_cell(position, c)?;
+
+        self.part_numbers_sum =
+            self.part_numbers_sum - part_numbers_before + self.part_numbers_around_sum(position);
+        self.gear_ratios_sum =
+            self.gear_ratios_sum - gear_ratios_before + self.gear_ratios_of(&gears);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use grid::Grid;
+    use proptest::prelude::*;
+
+    use super::*;
+
+    const CELLS: [char; 8] = ['.', '.', '.', '1', '2', '9', '*', '#'];
+
+    fn cell() -> impl Strategy<Value = char> {
+        prop::sample::select(CELLS.to_vec())
+    }
+
+    fn schematic_and_edits() -> impl Strategy<Value = (Vec<Vec<char>>, Vec<(Position, char)>)> {
+        (1..7_usize, 1..9_usize).prop_flat_map(|(lines, columns)| {
+            (
+                prop::collection::vec(prop::collection::vec(cell(), columns), lines),
+                prop::collection::vec(((0..lines, 0..columns), cell()), 1..40),
+            )
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn incremental_matches_full_recomputation((lines, edits) in schematic_and_edits()) {
+            let mut incremental =
+                IncrementalSchematic::new(Schematic::new(Grid::from_lines(lines).unwrap()));
+
+            for (position, c) in edits {
+                incremental.set_cell(position, c).unwrap();
+
+                let recomputed = Schematic::new(incremental.schematic().cells().clone());
+                prop_assert_eq!(incremental.part_numbers_sum(), recomputed.part_numbers_sum());
+                prop_assert_eq!(incremental.gear_ratios_sum(), recomputed.gear_ratios_sum());
+
+                let mut numbers = incremental.schematic().numbers().to_vec();
+                numbers.sort_by_key(|number| (number.line, *number.columns.start()));
+                prop_assert_eq!(numbers, recomputed.numbers().to_vec());
+                prop_assert_eq!(incremental.schematic().symbols(), recomputed.symbols());
+            }
+        }
+    }
+
+    #[test]
+    fn edits_outside_of_schematic_are_rejected() {
+        let mut incremental = IncrementalSchematic::new("1*1".parse().unwrap());
+        assert_eq!(
+            incremental.set_cell((1, 0), '2'),
+            Err(EditError::OutOfBounds((1, 0)))
+        );
+        assert_eq!(
+            incremental.set_cell((0, 1), '\n'),
+            Err(EditError::InvalidCell('\n'))
+        );
+        assert_eq!(incremental.gear_ratios_sum(), 1);
+    }
+}
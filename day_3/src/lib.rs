@@ -0,0 +1,6 @@
+pub mod graph;
+pub mod incremental;
+pub mod render;
+pub mod rules;
+pub mod schematic;
+pub mod stream;
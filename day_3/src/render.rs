@@ -0,0 +1,243 @@
+//! Annotated schematic rendering: part numbers, non-part numbers, symbols and valid gears
+//! are highlighted differently
+
+use std::fmt::Write;
+
+use crate::{
+    graph::{AdjacencyGraph, SymbolId},
+    schematic::{NumberId, Schematic, GEAR_SYMBOL},
+};
+
+const ANSI_RESET: &str = "\x1b[0m";
+const ANSI_PART_NUMBER: &str = "\x1b[32m";
+const ANSI_NON_PART_NUMBER: &str = "\x1b[31m";
+const ANSI_SYMBOL: &str = "\x1b[36m";
+const ANSI_GEAR: &str = "\x1b[1;33m";
+const ANSI_EMPTY: &str = "\x1b[2m";
+
+const HTML_STYLE: &str = "\
+body { background: #0f0f23; color: #cccccc; }
+pre { font-family: monospace; line-height: 1.2; }
+.empty { color: #555555; }
+.part { color: #00cc00; }
+.non-part { color: #ff4444; }
+.symbol { color: #00cccc; }
+.gear { color: #ffff66; font-weight: bold; }
+span[title]:hover { background: #333366; }";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum CellKind {
+    Empty,
+    PartNumber(NumberId),
+    NonPartNumber(NumberId),
+    Symbol(SymbolId),
+    Gear(SymbolId),
+}
+
+impl CellKind {
+    fn ansi_color(self) -> &'static str {
+        match self {
+            CellKind::Empty => ANSI_EMPTY,
+            CellKind::PartNumber(_) => ANSI_PART_NUMBER,
+            CellKind::NonPartNumber(_) => ANSI_NON_PART_NUMBER,
+            CellKind::Symbol(_) => ANSI_SYMBOL,
+            CellKind::Gear(_) => ANSI_GEAR,
+        }
+    }
+
+    fn html_class(self) -> &'static str {
+        match self {
+            CellKind::Empty => "empty",
+            CellKind::PartNumber(_) => "part",
+            CellKind::NonPartNumber(_) => "non-part",
+            CellKind::Symbol(_) => "symbol",
+            CellKind::Gear(_) => "gear",
+        }
+    }
+}
+
+fn cell_kind(schematic: &Schematic, graph: &AdjacencyGraph, position: (usize, usize)) -> CellKind {
+    if let Some(id) = schematic.number_at(position) {
+        return if graph.number_neighbors(id).is_empty() {
+            CellKind::NonPartNumber(id)
+        } else {
+            CellKind::PartNumber(id)
+        };
+    }
+    match graph.symbol_at(position) {
+        Some(id)
+            if graph.symbols()[id].1 == GEAR_SYMBOL && graph.symbol_neighbors(id).len() == 2 =>
+        {
+            CellKind::Gear(id)
+        }
+        Some(id) => CellKind::Symbol(id),
+        None => CellKind::Empty,
+    }
+}
+
+/// Groups the consecutive cells of the same kind, so a number becomes a single run
+fn runs(
+    schematic: &Schematic,
+    graph: &AdjacencyGraph,
+    i: usize,
+) -> impl Iterator<Item = (CellKind, String)> {
+    let mut runs: Vec<(CellKind, String)> = Vec::new();
+    for (j, c) in schematic
+        .cells()
+        .line(i)
+        .unwrap_or_default()
+        .iter()
+        .enumerate()
+    {
+        let kind = cell_kind(schematic, graph, (i, j));
+        match runs.last_mut() {
+            Some((last_kind, text)) if *last_kind == kind => text.push(*c),
+            _ => runs.push((kind, c.to_string())),
+        }
+    }
+    runs.into_iter()
+}
+
+pub fn render_ansi(schematic: &Schematic, graph: &AdjacencyGraph) -> String {
+    let mut rendered = String::new();
+    for i in 0..schematic.cells().lines() {
+        for (kind, text) in runs(schematic, graph, i) {
+            write!(rendered, "{}{text}{ANSI_RESET}", kind.ansi_color()).unwrap();
+        }
+        rendered.push('\n');
+    }
+    rendered
+}
+
+fn escape_html(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '&' => "&amp;".to_owned(),
+            '<' => "&lt;".to_owned(),
+            '>' => "&gt;".to_owned(),
+            '"' => "&quot;".to_owned(),
+            _ => c.to_string(),
+        })
+        .collect()
+}
+
+/// Tooltip listing the symbols adjacent to a number or the numbers adjacent to a symbol
+fn tooltip(graph: &AdjacencyGraph, kind: CellKind) -> Option<String> {
+    match kind {
+        CellKind::Empty => None,
+        CellKind::PartNumber(id) | CellKind::NonPartNumber(id) => {
+            let symbols: Vec<String> = graph
+                .number_neighbors(id)
+                .iter()
+                .map(|symbol_id| {
+                    let ((i, j), symbol) = graph.symbols()[*symbol_id];
+                    format!("{symbol} ({i},{j})")
+                })
+                .collect();
+            Some(if symbols.is_empty() {
+                "no adjacent symbols".to_owned()
+            } else {
+                format!("adjacent symbols: {}", symbols.join(", "))
+            })
+        }
+        CellKind::Symbol(id) | CellKind::Gear(id) => {
+            let numbers: Vec<String> = graph
+                .symbol_neighbors(id)
+                .iter()
+                .map(|number_id| graph.numbers()[*number_id].value.to_string())
+                .collect();
+            Some(format!("adjacent numbers: {}", numbers.join(", ")))
+        }
+    }
+}
+
+/// Standalone HTML page, hovering a number shows its adjacent symbols
+pub fn render_html(schematic: &Schematic, graph: &AdjacencyGraph) -> String {
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Engine schematic</title>\n<style>\n{HTML_STYLE}\n</style>\n</head>\n\
+         <body>\n<pre>\n"
+    );
+    for i in 0..schematic.cells().lines() {
+        for (kind, text) in runs(schematic, graph, i) {
+            let class = kind.html_class();
+            let text = escape_html(&text);
+            match tooltip(graph, kind) {
+                Some(tooltip) => write!(
+                    html,
+                    "<span class=\"{class}\" title=\"{}\">{text}</span>",
+                    escape_html(&tooltip)
+                ),
+                None => write!(html, "<span class=\"{class}\">{text}</span>"),
+            }
+            .unwrap();
+        }
+        html.push('\n');
+    }
+    html.push_str("</pre>\n</body>\n</html>\n");
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schematic::SAMPLE;
+
+    fn sample() -> (Schematic, AdjacencyGraph) {
+        let schematic: Schematic = SAMPLE.parse().unwrap();
+        let graph = AdjacencyGraph::new(&schematic);
+        (schematic, graph)
+    }
+
+    #[test]
+    fn cells_are_classified() {
+        let (schematic, graph) = sample();
+        let kind = |position| cell_kind(&schematic, &graph, position);
+
+        assert!(matches!(kind((0, 5)), CellKind::NonPartNumber(_)));
+        assert_eq!(kind((0, 7)), kind((0, 5)));
+        assert!(matches!(kind((0, 0)), CellKind::PartNumber(_)));
+        assert_eq!(
+            kind((1, 3)),
+            CellKind::Gear(graph.symbol_at((1, 3)).unwrap())
+        );
+        assert_eq!(
+            kind((4, 3)),
+            CellKind::Symbol(graph.symbol_at((4, 3)).unwrap())
+        );
+        assert_eq!(kind((0, 3)), CellKind::Empty);
+    }
+
+    #[test]
+    fn tooltips_list_the_neighbors() {
+        let (schematic, graph) = sample();
+        let tooltip_at = |position| tooltip(&graph, cell_kind(&schematic, &graph, position));
+
+        assert_eq!(
+            tooltip_at((0, 1)).as_deref(),
+            Some("adjacent symbols: * (1,3)")
+        );
+        assert_eq!(tooltip_at((0, 5)).as_deref(), Some("no adjacent symbols"));
+        assert_eq!(
+            tooltip_at((1, 3)).as_deref(),
+            Some("adjacent numbers: 467, 35")
+        );
+        assert_eq!(tooltip_at((4, 3)).as_deref(), Some("adjacent numbers: 617"));
+        assert_eq!(tooltip_at((0, 3)), None);
+    }
+
+    #[test]
+    fn html_is_escaped() {
+        let schematic: Schematic = "1\"\n<&".parse().unwrap();
+        let graph = AdjacencyGraph::new(&schematic);
+        let html = render_html(&schematic, &graph);
+
+        assert!(html.contains(
+            "<span class=\"part\" title=\"adjacent symbols: &quot; (0,1), &lt; (1,0), \
+             &amp; (1,1)\">1</span>"
+        ));
+        assert!(html.contains("title=\"adjacent numbers: 1\">&quot;</span>"));
+        assert!(html.contains("title=\"adjacent numbers: 1\">&lt;</span>"));
+        assert!(html.contains("title=\"adjacent numbers: 1\">&amp;</span>"));
+    }
+}
@@ -0,0 +1,32 @@
+use std::{env, error::Error, fs, fs::read_to_string};
+
+use day_3::{
+    graph::AdjacencyGraph,
+    render::{render_ansi, render_html},
+    schematic::Schematic,
+};
+
+const INPUT_FILE: &str = "input.txt";
+
+/// Prints the colored schematic, `--html PATH` writes it as a standalone page instead
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut html_path: Option<String> = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--html" => html_path = Some(args.next().ok_or("--html expects a file path")?),
+            _ => return Err(format!("unknown argument \"{arg}\"").into()),
+        }
+    }
+
+    let schematic: Schematic = read_to_string(INPUT_FILE)?.parse()?;
+    let graph = AdjacencyGraph::new(&schematic);
+
+    match html_path {
+        Some(path) => fs::write(path, render_html(&schematic, &graph))?,
+        None => print!("{}", render_ansi(&schematic, &graph)),
+    }
+
+    Ok(())
+}
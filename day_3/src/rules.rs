@@ -0,0 +1,354 @@
+//! Rules evaluating the symbols by the numbers adjacent to them
+//!
+//! A rule is written as `SYMBOLS:COUNT:AGGREGATION`, where
+//! - `SYMBOLS` are the matching symbols written together (e.g. `*#`) or `any`,
+//! - `COUNT` is the required quantity of the adjacent numbers: `N` (exactly), `>=N` or `<=N`,
+//! - `AGGREGATION` combines the adjacent numbers: `product`, `sum` or `max`.
+//!
+//! The gear rule of the part 2 is `*:2:product`.
+//!
+//! Every symbol is evaluated on its own, so the results don't depend on the order the symbols
+//! are visited in. Numbers adjacent to several symbols are handled by the `SharingPolicy`.
+
+use std::{fmt, str::FromStr};
+
+use grid::Position;
+
+use crate::schematic::Schematic;
+
+const ANY_SYMBOL: &str = "any";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SymbolClass {
+    Any,
+    OneOf(Vec<char>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CountRequirement {
+    Exactly(usize),
+    AtLeast(usize),
+    AtMost(usize),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Aggregation {
+    Product,
+    Sum,
+    Max,
+}
+
+/// What happens to a number adjacent to more than one symbol
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SharingPolicy {
+    /// Number is credited to each of the adjacent symbols
+    #[default]
+    Shared,
+    /// Number adjacent to more than one symbol matching the rule is ambiguous and isn't
+    /// credited to any of them, the symbols the rule ignores don't count
+    Exclusive,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SymbolRule {
+    pub symbols: SymbolClass,
+    pub count: CountRequirement,
+    pub aggregation: Aggregation,
+}
+
+/// Result of a single symbol satisfying the rule
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SymbolResult {
+    pub position: Position,
+    pub symbol: char,
+    pub value: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuleEvaluation {
+    /// Symbols satisfying the rule in the line-by-line order
+    pub per_symbol: Vec<SymbolResult>,
+    pub total: u64,
+}
+
+impl SymbolClass {
+    pub fn matches(&self, symbol: char) -> bool {
+        match self {
+            SymbolClass::Any => true,
+            SymbolClass::OneOf(symbols) => symbols.contains(&symbol),
+        }
+    }
+}
+
+impl CountRequirement {
+    pub fn is_satisfied(self, count: usize) -> bool {
+        match self {
+            CountRequirement::Exactly(required) => count == required,
+            CountRequirement::AtLeast(required) => count >= required,
+            CountRequirement::AtMost(required) => count <= required,
+        }
+    }
+}
+
+impl Aggregation {
+    pub fn apply(self, values: impl Iterator<Item = u64>) -> u64 {
+        match self {
+            Aggregation::Product => values.product(),
+            Aggregation::Sum => values.sum(),
+            Aggregation::Max => values.max().unwrap_or_default(),
+        }
+    }
+}
+
+impl SymbolRule {
+    /// `*` adjacent to exactly two numbers, its value is their product
+    pub fn gear() -> Self {
+        SymbolRule {
+            symbols: SymbolClass::OneOf(vec!['*']),
+            count: CountRequirement::Exactly(2),
+            aggregation: Aggregation::Product,
+        }
+    }
+
+    /// Evaluates a single symbol, `None` if it doesn't satisfy the rule
+    pub fn evaluate_symbol(
+        &self,
+        schematic: &Schematic,
+        sharing: SharingPolicy,
+        (position, symbol): (Position, char),
+    ) -> Option<SymbolResult> {
+        if !self.symbols.matches(symbol) {
+            return None;
+        }
+
+        let adjacent_numbers: Vec<u64> = schematic
+            .adjacent_numbers(position)
+            .into_iter()
+            .filter(|id| match sharing {
+                SharingPolicy::Shared => true,
+                SharingPolicy::Exclusive => {
+                    let cells = schematic.cells();
+                    schematic
+                        .adjacent_symbols(*id)
+                        .into_iter()
+                        .filter(|position| self.symbols.matches(cells[*position]))
+                        .count()
+                        == 1
+                }
+            })
+            .map(|id| schematic.numbers()[id].value)
+            .collect();
+
+        self.count
+            .is_satisfied(adjacent_numbers.len())
+            .then(|| SymbolResult {
+                position,
+                symbol,
+                value: self.aggregation.apply(adjacent_numbers.into_iter()),
+            })
+    }
+
+    pub fn evaluate(&self, schematic: &Schematic, sharing: SharingPolicy) -> RuleEvaluation {
+        let per_symbol: Vec<SymbolResult> = schematic
+            .symbols()
+            .iter()
+            .filter_map(|&symbol| self.evaluate_symbol(schematic, sharing, symbol))
+            .collect();
+
+        RuleEvaluation {
+            total: per_symbol.iter().map(|result| result.value).sum(),
+            per_symbol,
+        }
+    }
+}
+
+impl fmt::Display for SymbolRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.symbols {
+            SymbolClass::Any => f.write_str(ANY_SYMBOL)?,
+            SymbolClass::OneOf(symbols) => symbols.iter().try_for_each(|c| write!(f, "{c}"))?,
+        }
+        match self.count {
+            CountRequirement::Exactly(count) => write!(f, ":{count}:")?,
+            CountRequirement::AtLeast(count) => write!(f, ":>={count}:")?,
+            CountRequirement::AtMost(count) => write!(f, ":<={count}:")?,
+        }
+        f.write_str(match self.aggregation {
+            Aggregation::Product => "product",
+            Aggregation::Sum => "sum",
+            Aggregation::Max => "max",
+        })
+    }
+}
+
+impl FromStr for SharingPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "shared" => Ok(SharingPolicy::Shared),
+            "exclusive" => Ok(SharingPolicy::Exclusive),
+            _ => Err(format!(
+                "unknown sharing policy \"{s}\", expected \"shared\" or \"exclusive\""
+            )),
+        }
+    }
+}
+
+impl FromStr for SymbolRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Symbols go first since they may contain ':' themselves
+        let mut parts = s.rsplitn(3, ':');
+        let (Some(aggregation), Some(count), Some(symbols)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(format!(
+                "rule \"{s}\" doesn't match the SYMBOLS:COUNT:AGGREGATION format"
+            ));
+        };
+
+        let symbols = match symbols {
+            ANY_SYMBOL => SymbolClass::Any,
+            "" => return Err(format!("rule \"{s}\" has no symbols")),
+            symbols => SymbolClass::OneOf(symbols.chars().collect()),
+        };
+
+        let parse_count = |count: &str| {
+            count
+                .parse::<usize>()
+                .map_err(|_| format!("invalid count \"{count}\" in the rule \"{s}\""))
+        };
+        let count = if let Some(count) = count.strip_prefix(">=") {
+            CountRequirement::AtLeast(parse_count(count)?)
+        } else if let Some(count) = count.strip_prefix("<=") {
+            CountRequirement::AtMost(parse_count(count)?)
+        } else {
+            CountRequirement::Exactly(parse_count(count.strip_prefix('=').unwrap_or(count))?)
+        };
+
+        let aggregation = match aggregation {
+            "product" => Aggregation::Product,
+            "sum" => Aggregation::Sum,
+            "max" => Aggregation::Max,
+            _ => {
+                return Err(format!(
+                    "unknown aggregation \"{aggregation}\", expected product, sum or max"
+                ))
+            }
+        };
+
+        Ok(SymbolRule {
+            symbols,
+            count,
+            aggregation,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schematic::SAMPLE;
+
+    /// `2` touches both gears, `3` and `5` touch only one of them
+    const SHARED_NUMBER: &str = "\
+3*2*5
+.....";
+
+    /// Mirrors the schematic both vertically and horizontally, which reverses the order
+    /// the symbols are visited in
+    fn mirrored(schematic: &str) -> String {
+        schematic
+            .lines()
+            .rev()
+            .map(|line| {
+                // Numbers keep their digits order, only their placement is mirrored
+                let mut mirrored: Vec<String> = Vec::new();
+                let mut chars = line.chars().peekable();
+                while let Some(c) = chars.next() {
+                    let mut token = c.to_string();
+                    while c.is_ascii_digit() && chars.peek().is_some_and(char::is_ascii_digit) {
+                        token.push(chars.next().unwrap());
+                    }
+                    mirrored.push(token);
+                }
+                mirrored.into_iter().rev().collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn shared_number_is_credited_by_policy() {
+        let schematic: Schematic = SHARED_NUMBER.parse().unwrap();
+        let rule = SymbolRule::gear();
+
+        let shared = rule.evaluate(&schematic, SharingPolicy::Shared);
+        assert_eq!(
+            shared
+                .per_symbol
+                .iter()
+                .map(|r| r.value)
+                .collect::<Vec<_>>(),
+            vec![6, 10]
+        );
+        assert_eq!(shared.total, 16);
+
+        let exclusive = rule.evaluate(&schematic, SharingPolicy::Exclusive);
+        assert!(exclusive.per_symbol.is_empty());
+        assert_eq!(exclusive.total, 0);
+    }
+
+    #[test]
+    fn exclusive_policy_ignores_symbols_outside_the_rule() {
+        // `2` touches the gear and `#`, which the gear rule doesn't match
+        let schematic: Schematic = "3*2#\n....".parse().unwrap();
+        let gear = SymbolRule::gear().evaluate(&schematic, SharingPolicy::Exclusive);
+        assert_eq!(gear.total, 6);
+
+        let rule: SymbolRule = "*#:>=1:sum".parse().unwrap();
+        let evaluation = rule.evaluate(&schematic, SharingPolicy::Exclusive);
+        assert_eq!(
+            evaluation
+                .per_symbol
+                .iter()
+                .map(|r| (r.symbol, r.value))
+                .collect::<Vec<_>>(),
+            vec![('*', 3)]
+        );
+    }
+
+    #[test]
+    fn evaluation_does_not_depend_on_symbols_order() {
+        for input in [SAMPLE, SHARED_NUMBER] {
+            let schematic: Schematic = input.parse().unwrap();
+            let mirrored: Schematic = mirrored(input).parse().unwrap();
+
+            for sharing in [SharingPolicy::Shared, SharingPolicy::Exclusive] {
+                for rule in [SymbolRule::gear(), "any:>=1:sum".parse().unwrap()] {
+                    let total = rule.evaluate(&schematic, sharing).total;
+
+                    assert_eq!(rule.evaluate(&mirrored, sharing).total, total);
+
+                    let reversed_total: u64 = schematic
+                        .symbols()
+                        .iter()
+                        .rev()
+                        .filter_map(|&symbol| rule.evaluate_symbol(&schematic, sharing, symbol))
+                        .map(|result| result.value)
+                        .sum();
+                    assert_eq!(reversed_total, total);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn gear_ratios_sum() {
+        let schematic: Schematic = SAMPLE.parse().unwrap();
+        let evaluation = SymbolRule::gear().evaluate(&schematic, SharingPolicy::Shared);
+        assert_eq!(evaluation.total, 467835);
+    }
+}
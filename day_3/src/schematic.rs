@@ -0,0 +1,338 @@
+use std::{error::Error, fmt, ops::RangeInclusive, str::FromStr};
+
+use grid::{parse_grid, Grid, Position};
+use nom::{
+    character::complete::{multispace0, none_of},
+    combinator::all_consuming,
+    sequence::terminated,
+};
+
+pub const EMPTY_CELL: char = '.';
+pub const GEAR_SYMBOL: char = '*';
+
+/// Index of a number in the `Schematic::numbers`
+pub type NumberId = usize;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Number {
+    pub value: u64,
+    pub line: usize,
+    pub columns: RangeInclusive<usize>,
+}
+
+/// Engine schematic where each cell knows the number it belongs to, so finding the numbers
+/// adjacent to a symbol doesn't require scanning all of them
+#[derive(Clone, Debug)]
+pub struct Schematic {
+    cells: Grid<char>,
+    numbers: Vec<Number>,
+    number_ids: Grid<Option<NumberId>>,
+    /// Symbols in the line-by-line order
+    symbols: Vec<(Position, char)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EditError {
+    OutOfBounds(Position),
+    /// Line breaks can't be placed into a cell
+    InvalidCell(char),
+}
+
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EditError::OutOfBounds((i, j)) => write!(f, "cell {i},{j} is out of the schematic"),
+            EditError::InvalidCell(c) => write!(f, "{c:?} can't be placed into a cell"),
+        }
+    }
+}
+
+impl Error for EditError {}
+
+pub fn is_symbol(c: char) -> bool {
+    c != EMPTY_CELL && !c.is_ascii_digit()
+}
+
+/// Values and columns of the numbers of a line, from left to right
+pub fn scan_numbers(line: &[char]) -> Vec<(u64, RangeInclusive<usize>)> {
+    let mut numbers = Vec::new();
+    let mut current_number: Option<(u64, usize)> = None;
+    // Trailing '.' terminates a number at the end of the line
+    for (j, c) in line.iter().copied().chain([EMPTY_CELL]).enumerate() {
+        if let Some(digit) = c.to_digit(10) {
+            current_number = Some(match current_number {
+                Some((value, start)) => (value * 10 + digit as u64, start),
+                None => (digit as u64, j),
+            });
+        } else if let Some((value, start)) = current_number.take() {
+            numbers.push((value, start..=j - 1));
+        }
+    }
+    numbers
+}
+
+impl Schematic {
+    pub fn new(cells: Grid<char>) -> Self {
+        let mut numbers: Vec<Number> = Vec::with_capacity(256);
+        let mut number_ids = Grid::from_lines(vec![vec![None; cells.columns()]; cells.lines()])
+            .expect("lines have the same length");
+        let mut symbols = Vec::with_capacity(128);
+
+        for (i, line) in cells.iter_lines().enumerate() {
+            symbols.extend(
+                line.iter()
+                    .enumerate()
+                    .filter(|(_, c)| is_symbol(**c))
+                    .map(|(j, c)| ((i, j), *c)),
+            );
+            for (value, columns) in scan_numbers(line) {
+                for column in columns.clone() {
+                    number_ids[(i, column)] = Some(numbers.len());
+                }
+                numbers.push(Number {
+                    value,
+                    line: i,
+                    columns,
+                });
+            }
+        }
+
+        Schematic {
+            cells,
+            numbers,
+            number_ids,
+            symbols,
+        }
+    }
+
+    pub fn cells(&self) -> &Grid<char> {
+        &self.cells
+    }
+
+    pub fn numbers(&self) -> &[Number] {
+        &self.numbers
+    }
+
+    pub fn symbols(&self) -> &[(Position, char)] {
+        &self.symbols
+    }
+
+    /// Number occupying the cell
+    pub fn number_at(&self, position: Position) -> Option<NumberId> {
+        self.number_ids.get(position).copied().flatten()
+    }
+
+    /// Distinct numbers adjacent to the cell in ascending order
+    pub fn adjacent_numbers(&self, position: Position) -> Vec<NumberId> {
+        let mut ids: Vec<NumberId> = self
+            .cells
+            .neighbors8(position)
+            .filter_map(|neighbor| self.number_at(neighbor))
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Distinct symbols adjacent to the number in the line-by-line order
+    pub fn adjacent_symbols(&self, id: NumberId) -> Vec<Position> {
+        let number = &self.numbers[id];
+        let mut positions: Vec<Position> = number
+            .columns
+            .clone()
+            .flat_map(|column| self.cells.neighbors8((number.line, column)))
+            .filter(|neighbor| is_symbol(self.cells[*neighbor]))
+            .collect();
+        positions.sort_unstable();
+        positions.dedup();
+        positions
+    }
+
+    /// Whether the number is adjacent to any symbol
+    pub fn is_part_number(&self, id: NumberId) -> bool {
+        let number = &self.numbers[id];
+        number.columns.clone().any(|column| {
+            self.cells
+                .neighbors8((number.line, column))
+                .any(|neighbor| is_symbol(self.cells[neighbor]))
+        })
+    }
+
+    /// Product of the two numbers adjacent to the gear, `None` if there is no such gear
+    pub fn gear_ratio(&self, position: Position) -> Option<u64> {
+        if self.cells.get(position) != Some(&GEAR_SYMBOL) {
+            return None;
+        }
+        let adjacent_numbers = self.adjacent_numbers(position);
+        (adjacent_numbers.len() == 2).then(|| {
+            adjacent_numbers
+                .into_iter()
+                .map(|id| self.numbers[id].value)
+                .product()
+        })
+    }
+
+    pub fn gear_ratios_sum(&self) -> u64 {
+        self.symbols
+            .iter()
+            .filter_map(|(position, _)| self.gear_ratio(*position))
+            .sum()
+    }
+
+    /// Places a digit, a symbol or '.' into the cell. Only the numbers of the cell line next to
+    /// it are rebuilt, though ids of the other numbers may change as well
+    pub fn set_cell(&mut self, position: Position, c: char) -> Result<(), EditError> {
+        let (i, j) = position;
+        let old = *self
+            .cells
+            .get(position)
+            .ok_or(EditError::OutOfBounds(position))?;
+        if c == '\n' || c == '\r' {
+            return Err(EditError::InvalidCell(c));
+        }
+        if old == c {
+            return Ok(());
+        }
+
+        if is_symbol(old) {
+            if let Ok(k) = self.symbols.binary_search_by_key(&position, |(p, _)| *p) {
+                self.symbols.remove(k);
+            }
+        }
+        if is_symbol(c) {
+            let k = self
+                .symbols
+                .binary_search_by_key(&position, |(p, _)| *p)
+                .unwrap_or_else(|k| k);
+            self.symbols.insert(k, (position, c));
+        }
+        self.cells[position] = c;
+
+        // Numbers which may be split, merged, extended or shrunk by the edit
+        let mut touched: Vec<NumberId> = (j.saturating_sub(1)..=j + 1)
+            .filter_map(|column| self.number_at((i, column)))
+            .collect();
+        touched.sort_unstable();
+        touched.dedup();
+
+        let from = touched
+            .iter()
+            .map(|id| *self.numbers[*id].columns.start())
+            .fold(j, usize::min);
+        let to = touched
+            .iter()
+            .map(|id| *self.numbers[*id].columns.end())
+            .fold(j, usize::max);
+
+        // Removal from the end keeps the ids of the remaining touched numbers valid
+        for id in touched.into_iter().rev() {
+            self.remove_number(id);
+        }
+
+        let mut current_number: Option<(u64, usize)> = None;
+        for column in from..=to + 1 {
+            let digit = self.cells.get((i, column)).and_then(|c| c.to_digit(10));
+            match (digit, current_number) {
+                (Some(digit), Some((value, start))) => {
+                    current_number = Some((value * 10 + digit as u64, start))
+                }
+                (Some(digit), None) => current_number = Some((digit as u64, column)),
+                (None, Some((value, start))) => {
+                    self.push_number(Number {
+                        value,
+                        line: i,
+                        columns: start..=column - 1,
+                    });
+                    current_number = None;
+                }
+                (None, None) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn push_number(&mut self, number: Number) {
+        for column in number.columns.clone() {
+            self.number_ids[(number.line, column)] = Some(self.numbers.len());
+        }
+        self.numbers.push(number);
+    }
+
+    /// Removes the number, the last number takes its id
+    fn remove_number(&mut self, id: NumberId) {
+        let removed = self.numbers.swap_remove(id);
+        for column in removed.columns {
+            self.number_ids[(removed.line, column)] = None;
+        }
+        if let Some(moved) = self.numbers.get(id) {
+            for column in moved.columns.clone() {
+                self.number_ids[(moved.line, column)] = Some(id);
+            }
+        }
+    }
+
+    pub fn part_numbers_sum(&self) -> u64 {
+        let mut is_part_number = vec![false; self.numbers.len()];
+        for &(position, _) in &self.symbols {
+            for id in self.adjacent_numbers(position) {
+                is_part_number[id] = true;
+            }
+        }
+        self.numbers
+            .iter()
+            .zip(is_part_number)
+            .filter(|(_, is_part_number)| *is_part_number)
+            .map(|(number, _)| number.value)
+            .sum()
+    }
+}
+
+impl FromStr for Schematic {
+    type Err = nom::Err<nom::error::Error<String>>;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        all_consuming(terminated(parse_grid(none_of("\r\n")), multispace0))(s)
+            .map(|(_, cells)| Schematic::new(cells))
+            .map_err(|err| err.to_owned())
+    }
+}
+
+/// Example schematic of the puzzle
+#[cfg(test)]
+pub(crate) const SAMPLE: &str = "\
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbers_are_scanned_up_to_the_line_end() {
+        let line: Vec<char> = "12.*3..456".chars().collect();
+        assert_eq!(
+            scan_numbers(&line),
+            vec![(12, 0..=1), (3, 4..=4), (456, 7..=9)]
+        );
+        assert!(scan_numbers(&[]).is_empty());
+        assert!(scan_numbers(&['.', '#']).is_empty());
+    }
+
+    #[test]
+    fn sample_is_indexed() {
+        let schematic: Schematic = SAMPLE.parse().unwrap();
+        assert_eq!(schematic.numbers().len(), 10);
+        assert_eq!(schematic.symbols().len(), 6);
+        assert_eq!(schematic.number_at((9, 3)), schematic.number_at((9, 1)));
+        assert_eq!(schematic.part_numbers_sum(), 4361);
+    }
+}
@@ -0,0 +1,167 @@
+//! Streaming schematic scanner keeping only three lines at once
+//!
+//! A line is complete as soon as the next one arrives: every symbol which may touch its numbers
+//! and every number which may touch its gears is known by then. So memory usage doesn't depend
+//! on the schematic height.
+
+use std::{collections::VecDeque, ops::RangeInclusive};
+
+use crate::schematic::{is_symbol, scan_numbers, GEAR_SYMBOL};
+
+const WINDOW_SIZE: usize = 3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StreamEvent {
+    /// Number adjacent to a symbol, `line` is where the number is placed
+    PartNumber { line: usize, value: u64 },
+    /// Product of the two numbers adjacent to the gear at the `line`
+    GearRatio { line: usize, value: u64 },
+}
+
+#[derive(Debug)]
+struct Line {
+    cells: Vec<char>,
+    numbers: Vec<(u64, RangeInclusive<usize>)>,
+}
+
+impl Line {
+    fn new(line: &str) -> Self {
+        let cells: Vec<char> = line.chars().collect();
+        let numbers = scan_numbers(&cells);
+        Line { cells, numbers }
+    }
+
+    fn has_symbol_within(&self, columns: RangeInclusive<usize>) -> bool {
+        columns
+            .filter_map(|column| self.cells.get(column))
+            .any(|c| is_symbol(*c))
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct WindowScanner {
+    /// Previous, current and next lines at most
+    window: VecDeque<Line>,
+    /// Index of the first line in the window
+    first_line: usize,
+}
+
+impl WindowScanner {
+    pub fn new() -> Self {
+        WindowScanner::default()
+    }
+
+    /// Adds the next line, returns the events of the line which became complete
+    pub fn push_line(&mut self, line: &str) -> Vec<StreamEvent> {
+        self.window.push_back(Line::new(line));
+        match self.window.len() {
+            // The first line is complete when the second one arrives
+            2 => self.complete_line(0),
+            WINDOW_SIZE => {
+                let events = self.complete_line(1);
+                self.window.pop_front();
+                self.first_line += 1;
+                events
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Completes the last line, there are no lines after it
+    pub fn finish(mut self) -> Vec<StreamEvent> {
+        match self.window.len() {
+            0 => Vec::new(),
+            len => {
+                let events = self.complete_line(len - 1);
+                self.window.clear();
+                events
+            }
+        }
+    }
+
+    /// Lines of the window around the `k`-th one
+    fn neighborhood(&self, k: usize) -> impl Iterator<Item = &Line> {
+        self.window
+            .range(k.saturating_sub(1)..(k + 2).min(self.window.len()))
+    }
+
+    fn complete_line(&self, k: usize) -> Vec<StreamEvent> {
+        let line = &self.window[k];
+        let index = self.first_line + k;
+        let mut events = Vec::new();
+
+        for (value, columns) in &line.numbers {
+            let around = columns.start().saturating_sub(1)..=columns.end() + 1;
+            if self
+                .neighborhood(k)
+                .any(|line| line.has_symbol_within(around.clone()))
+            {
+                events.push(StreamEvent::PartNumber {
+                    line: index,
+                    value: *value,
+                });
+            }
+        }
+
+        for (j, _) in line
+            .cells
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| **c == GEAR_SYMBOL)
+        {
+            let adjacent_numbers: Vec<u64> = self
+                .neighborhood(k)
+                .flat_map(|line| &line.numbers)
+                .filter(|(_, columns)| {
+                    *columns.start() <= j + 1 && j <= columns.end().saturating_add(1)
+                })
+                .map(|(value, _)| *value)
+                .collect();
+            if adjacent_numbers.len() == 2 {
+                events.push(StreamEvent::GearRatio {
+                    line: index,
+                    value: adjacent_numbers.iter().product(),
+                });
+            }
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        graph::AdjacencyGraph,
+        schematic::{Schematic, SAMPLE},
+    };
+
+    fn streamed_sums(input: &str) -> (u64, u64) {
+        let mut scanner = WindowScanner::new();
+        let mut events: Vec<StreamEvent> = input
+            .lines()
+            .flat_map(|line| scanner.push_line(line))
+            .collect();
+        events.extend(scanner.finish());
+
+        events
+            .into_iter()
+            .fold((0, 0), |(part_numbers, gear_ratios), event| match event {
+                StreamEvent::PartNumber { value, .. } => (part_numbers + value, gear_ratios),
+                StreamEvent::GearRatio { value, .. } => (part_numbers, gear_ratios + value),
+            })
+    }
+
+    #[test]
+    fn streaming_matches_two_pass() {
+        for input in [SAMPLE, "1*1", "2\n*\n3", "5.\n.*\n7.", "12*3\n....\n4*.5"] {
+            let graph = AdjacencyGraph::new(&input.parse::<Schematic>().unwrap());
+            assert_eq!(
+                streamed_sums(input),
+                (graph.part_numbers_sum(), graph.gear_ratios_sum()),
+                "{input}"
+            );
+        }
+    }
+}
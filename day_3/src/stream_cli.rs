@@ -0,0 +1,64 @@
+use std::{
+    env,
+    error::Error,
+    fs::File,
+    io::{self, BufRead, BufReader},
+};
+
+use day_3::stream::{StreamEvent, WindowScanner};
+
+const INPUT_FILE: &str = "input.txt";
+const STDIN_SOURCE: &str = "-";
+
+/// Scans the schematic line by line and prints the part numbers sum and the gear ratios sum,
+/// `--events` prints every part number and gear ratio as soon as its line is complete
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut source = INPUT_FILE.to_owned();
+    let mut print_events = false;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--events" => print_events = true,
+            "--input" => source = args.next().ok_or("--input expects a file path or \"-\"")?,
+            _ => return Err(format!("unknown argument \"{arg}\"").into()),
+        }
+    }
+
+    let reader: Box<dyn BufRead> = if source == STDIN_SOURCE {
+        Box::new(io::stdin().lock())
+    } else {
+        Box::new(BufReader::new(File::open(source)?))
+    };
+
+    let (mut part_numbers_sum, mut gear_ratios_sum) = (0, 0);
+    let mut handle_events = |events: Vec<StreamEvent>| {
+        for event in events {
+            match event {
+                StreamEvent::PartNumber { line, value } => {
+                    part_numbers_sum += value;
+                    if print_events {
+                        println!("{line} part number {value}");
+                    }
+                }
+                StreamEvent::GearRatio { line, value } => {
+                    gear_ratios_sum += value;
+                    if print_events {
+                        println!("{line} gear ratio {value}");
+                    }
+                }
+            }
+        }
+    };
+
+    let mut scanner = WindowScanner::new();
+    for line in reader.lines() {
+        handle_events(scanner.push_line(&line?));
+    }
+    handle_events(scanner.finish());
+
+    println!("{part_numbers_sum}");
+    println!("{gear_ratios_sum}");
+
+    Ok(())
+}
@@ -0,0 +1,211 @@
+use std::{collections::HashSet, error::Error, fmt};
+
+use nom::{
+    bytes::complete::tag,
+    character::complete::{digit1, line_ending, multispace0, space1},
+    combinator::{all_consuming, map_res},
+    multi::separated_list1,
+    sequence::{delimited, preceded, separated_pair, terminated, tuple},
+    IResult,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Card {
+    pub id: u64,
+    pub winning: Vec<u64>,
+    pub have: Vec<u64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CardError {
+    /// Holds the line which couldn't be parsed
+    Syntax(String),
+    /// Cards must be numbered sequentially starting from 1
+    UnexpectedId {
+        expected: u64,
+        found: u64,
+    },
+    DuplicateWinningNumber {
+        card: u64,
+        number: u64,
+    },
+    DuplicateNumberWeHave {
+        card: u64,
+        number: u64,
+    },
+}
+
+impl fmt::Display for CardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CardError::Syntax(line) => write!(f, "unable to parse the card \"{line}\""),
+            CardError::UnexpectedId { expected, found } => {
+                write!(f, "expected card {expected}, found card {found}")
+            }
+            CardError::DuplicateWinningNumber { card, number } => {
+                write!(
+                    f,
+                    "card {card} has the winning number {number} more than once"
+                )
+            }
+            CardError::DuplicateNumberWeHave { card, number } => {
+                write!(
+                    f,
+                    "card {card} has the number {number} we have more than once"
+                )
+            }
+        }
+    }
+}
+
+impl Error for CardError {}
+
+impl Card {
+    /// Quantity of the numbers we have among the winning ones
+    pub fn matches(&self) -> usize {
+        let winning: HashSet<u64> = HashSet::from_iter(self.winning.iter().copied());
+        let have: HashSet<u64> = HashSet::from_iter(self.have.iter().copied());
+        winning.intersection(&have).count()
+    }
+}
+
+fn parse_number(input: &str) -> IResult<&str, u64> {
+    map_res(digit1, |s: &str| s.parse::<u64>())(input)
+}
+
+fn parse_card_header(input: &str) -> IResult<&str, u64> {
+    delimited(
+        tuple((tag("Card"), space1)),
+        parse_number,
+        tuple((tag(":"), space1)),
+    )(input)
+}
+
+fn parse_card_lists(input: &str) -> IResult<&str, (Vec<u64>, Vec<u64>)> {
+    separated_pair(
+        terminated(separated_list1(space1, parse_number), space1),
+        tag("|"),
+        preceded(space1, separated_list1(space1, parse_number)),
+    )(input)
+}
+
+pub fn parse_card(input: &str) -> IResult<&str, Card> {
+    let (input, id) = parse_card_header(input)?;
+    let (input, (winning, have)) = parse_card_lists(input)?;
+    Ok((input, Card { id, winning, have }))
+}
+
+pub fn parse_cards(input: &str) -> IResult<&str, Vec<Card>> {
+    separated_list1(line_ending, parse_card)(input)
+}
+
+fn find_duplicate(numbers: &[u64]) -> Option<u64> {
+    let mut seen = HashSet::with_capacity(numbers.len());
+    numbers.iter().copied().find(|number| !seen.insert(*number))
+}
+
+/// Parses the whole input and checks that the cards are numbered sequentially and that
+/// their lists have no repeated numbers
+pub fn parse_valid_cards(input: &str) -> Result<Vec<Card>, CardError> {
+    let cards = all_consuming(terminated(parse_cards, multispace0))(input)
+        .map(|(_, cards)| cards)
+        .map_err(|err| match err {
+            nom::Err::Error(err) | nom::Err::Failure(err) => {
+                // Whole line the parsing stopped at
+                let offset = input.len() - err.input.len();
+                let start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+                CardError::Syntax(input[start..].lines().next().unwrap_or_default().to_owned())
+            }
+            nom::Err::Incomplete(_) => CardError::Syntax(String::new()),
+        })?;
+
+    for (expected, card) in (1..).zip(&cards) {
+        if card.id != expected {
+            return Err(CardError::UnexpectedId {
+                expected,
+                found: card.id,
+            });
+        }
+        if let Some(number) = find_duplicate(&card.winning) {
+            return Err(CardError::DuplicateWinningNumber {
+                card: card.id,
+                number,
+            });
+        }
+        if let Some(number) = find_duplicate(&card.have) {
+            return Err(CardError::DuplicateNumberWeHave {
+                card: card.id,
+                number,
+            });
+        }
+    }
+
+    Ok(cards)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crlf_lines_are_accepted() {
+        let cards = parse_valid_cards("Card 1: 1 2 | 2 3\r\nCard 2: 4 | 4\r\n").unwrap();
+        assert_eq!(
+            cards,
+            vec![
+                Card {
+                    id: 1,
+                    winning: vec![1, 2],
+                    have: vec![2, 3]
+                },
+                Card {
+                    id: 2,
+                    winning: vec![4],
+                    have: vec![4]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn ids_must_be_sequential() {
+        assert_eq!(
+            parse_valid_cards("Card 1: 1 | 2\nCard 3: 1 | 2\n"),
+            Err(CardError::UnexpectedId {
+                expected: 2,
+                found: 3
+            })
+        );
+        assert_eq!(
+            parse_valid_cards("Card 2: 1 | 2\nCard 1: 1 | 2\n"),
+            Err(CardError::UnexpectedId {
+                expected: 1,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn duplicate_numbers_are_rejected() {
+        assert_eq!(
+            parse_valid_cards("Card 1: 1 | 2\nCard 2: 5 7 5 | 2\n"),
+            Err(CardError::DuplicateWinningNumber { card: 2, number: 5 })
+        );
+        assert_eq!(
+            parse_valid_cards("Card 1: 1 2 | 3 4 3\n"),
+            Err(CardError::DuplicateNumberWeHave { card: 1, number: 3 })
+        );
+    }
+
+    #[test]
+    fn syntax_error_reports_the_line() {
+        assert_eq!(
+            parse_valid_cards("Card 1: 1 | 2\nCard 2: 1 x | 2\nCard 3: 1 | 2\n"),
+            Err(CardError::Syntax("Card 2: 1 x | 2".to_owned()))
+        );
+        assert_eq!(
+            parse_valid_cards("Card 1: 1 | 2 x\r\nCard 2: 1 | 2\r\n"),
+            Err(CardError::Syntax("Card 1: 1 | 2 x".to_owned()))
+        );
+    }
+}
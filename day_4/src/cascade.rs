@@ -0,0 +1,274 @@
+//! Copy cascade computed in a single pass over the cards
+//!
+//! Copies granted by a card apply to a contiguous run of the following cards, so only the
+//! quantity of copies currently being granted and the moments the grants expire are kept.
+//! Every card is handled in amortized constant time and the memory is bounded by the largest
+//! quantity of matches, cards are never copied past the end of the table.
+//!
+//! Copies double with every winning card, so the counts are kept by an `Arithmetic`: machine
+//! integers with overflow detection, big integers or integers modulo a given number.
+
+use std::{collections::VecDeque, error::Error, fmt};
+
+use num_bigint::BigUint;
+
+/// Card copies didn't fit into the machine integer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Overflow {
+    /// Position of the card in the table, starting from 1
+    pub card: usize,
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "quantity of cards overflowed at the card {}, count them exactly or modulo a number",
+            self.card
+        )
+    }
+}
+
+impl Error for Overflow {}
+
+/// How the quantities of cards are represented and added up
+pub trait Arithmetic {
+    type Count: Clone;
+
+    fn zero(&self) -> Self::Count;
+    fn one(&self) -> Self::Count;
+    /// `None` if the sum isn't representable
+    fn add(&self, a: &Self::Count, b: &Self::Count) -> Option<Self::Count>;
+    /// `b` never exceeds `a` since it has been added to `a` before
+    fn sub(&self, a: &Self::Count, b: &Self::Count) -> Self::Count;
+}
+
+/// `usize` counts, overflow is reported
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Checked;
+
+/// Arbitrary-precision counts
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Exact;
+
+/// Counts modulo the number, the cascade only adds and subtracts, so any modulus above 1
+/// gives the exact remainder
+#[derive(Clone, Copy, Debug)]
+pub struct Modulo(pub u64);
+
+impl Arithmetic for Checked {
+    type Count = usize;
+
+    fn zero(&self) -> usize {
+        0
+    }
+
+    fn one(&self) -> usize {
+        1
+    }
+
+    fn add(&self, a: &usize, b: &usize) -> Option<usize> {
+        a.checked_add(*b)
+    }
+
+    fn sub(&self, a: &usize, b: &usize) -> usize {
+        a - b
+    }
+}
+
+impl Arithmetic for Exact {
+    type Count = BigUint;
+
+    fn zero(&self) -> BigUint {
+        BigUint::ZERO
+    }
+
+    fn one(&self) -> BigUint {
+        BigUint::from(1_u8)
+    }
+
+    fn add(&self, a: &BigUint, b: &BigUint) -> Option<BigUint> {
+        Some(a + b)
+    }
+
+    fn sub(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        a - b
+    }
+}
+
+impl Arithmetic for Modulo {
+    type Count = u64;
+
+    fn zero(&self) -> u64 {
+        0
+    }
+
+    fn one(&self) -> u64 {
+        1 % self.0
+    }
+
+    fn add(&self, a: &u64, b: &u64) -> Option<u64> {
+        Some(((*a as u128 + *b as u128) % self.0 as u128) as u64)
+    }
+
+    fn sub(&self, a: &u64, b: &u64) -> u64 {
+        ((*a as u128 + self.0 as u128 - *b as u128) % self.0 as u128) as u64
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CopyCascade<A: Arithmetic = Checked> {
+    arithmetic: A,
+    /// Copies granted to the next card by the previous ones
+    active: A::Count,
+    /// `expiring[k]` copies stop being granted before the `k + 1`-th next card
+    expiring: VecDeque<A::Count>,
+    total: A::Count,
+    cards: usize,
+}
+
+impl Default for CopyCascade {
+    fn default() -> Self {
+        CopyCascade::new(Checked)
+    }
+}
+
+impl<A: Arithmetic> CopyCascade<A> {
+    pub fn new(arithmetic: A) -> Self {
+        CopyCascade {
+            active: arithmetic.zero(),
+            expiring: VecDeque::new(),
+            total: arithmetic.zero(),
+            cards: 0,
+            arithmetic,
+        }
+    }
+
+    /// Adds the next card by the quantity of its matches, returns its copies
+    pub fn push(&mut self, matches: usize) -> Result<A::Count, Overflow> {
+        self.cards += 1;
+        let overflow = Overflow { card: self.cards };
+        let arithmetic = &self.arithmetic;
+
+        if let Some(expired) = self.expiring.pop_front() {
+            self.active = arithmetic.sub(&self.active, &expired);
+        }
+        let copies = arithmetic
+            .add(&arithmetic.one(), &self.active)
+            .ok_or(overflow)?;
+        self.total = arithmetic.add(&self.total, &copies).ok_or(overflow)?;
+
+        if matches > 0 {
+            if self.expiring.len() <= matches {
+                self.expiring.resize(matches + 1, arithmetic.zero());
+            }
+            self.active = arithmetic.add(&self.active, &copies).ok_or(overflow)?;
+            self.expiring[matches] = arithmetic
+                .add(&self.expiring[matches], &copies)
+                .ok_or(overflow)?;
+        }
+
+        Ok(copies)
+    }
+
+    /// Cards counted so far, copies included
+    pub fn total(&self) -> &A::Count {
+        &self.total
+    }
+}
+
+/// Total quantity of the cards, copies included, given the matches of every card
+pub fn count_cards<A: Arithmetic>(
+    arithmetic: A,
+    matches: impl IntoIterator<Item = usize>,
+) -> Result<A::Count, Overflow> {
+    let mut cascade = CopyCascade::new(arithmetic);
+    for matches in matches {
+        cascade.push(matches)?;
+    }
+    Ok(cascade.total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{card::parse_valid_cards, explain::explain_cascade};
+
+    const CARDS: &str = "\
+Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+
+    const PRIME: u64 = 1_000_000_007;
+
+    fn count(matches: impl IntoIterator<Item = usize>) -> usize {
+        count_cards(Checked, matches).unwrap()
+    }
+
+    /// Every card copies all the following ones, so the card `k` has `2^(k - 1)` copies
+    fn adversarial(cards: usize) -> Vec<usize> {
+        (0..cards).map(|i| cards - i - 1).collect()
+    }
+
+    #[test]
+    fn cascade_matches_explanation() {
+        let cards = parse_valid_cards(CARDS).unwrap();
+        let mut cascade = CopyCascade::default();
+        let copies: Vec<usize> = cards
+            .iter()
+            .map(|card| cascade.push(card.matches()).unwrap())
+            .collect();
+
+        let explained: Vec<usize> = explain_cascade(&Checked, &cards)
+            .unwrap()
+            .iter()
+            .map(|card| card.copies)
+            .collect();
+        assert_eq!(copies, explained);
+        assert_eq!(*cascade.total(), 30);
+    }
+
+    #[test]
+    fn last_cards_are_not_copied_past_the_end() {
+        assert_eq!(count([5]), 1);
+        assert_eq!(count([0, 3]), 2);
+        assert_eq!(count([2, 4, 1]), 1 + 2 + 4);
+        assert_eq!(count([10, 10, 10, 10]), 1 + 2 + 4 + 8);
+    }
+
+    #[test]
+    fn grants_expire_after_their_run() {
+        // Card 1 copies only the cards 2 and 3, card 4 gets nothing
+        assert_eq!(count([2, 0, 0, 0]), 1 + 2 + 2 + 1);
+        // Grants of different lengths expiring at the same card
+        assert_eq!(count([2, 1, 0, 0]), 1 + 2 + 4 + 1);
+    }
+
+    #[test]
+    fn empty_table_has_no_cards() {
+        assert_eq!(count([]), 0);
+    }
+
+    #[test]
+    fn overflow_is_detected() {
+        let bits = usize::BITS as usize;
+        assert_eq!(count(adversarial(bits)), usize::MAX);
+        assert_eq!(
+            count_cards(Checked, adversarial(bits + 1)),
+            Err(Overflow { card: bits + 1 })
+        );
+    }
+
+    #[test]
+    fn exact_and_modular_counts_agree() {
+        let cards = 200;
+        let exact = count_cards(Exact, adversarial(cards)).unwrap();
+        assert_eq!(exact, (BigUint::from(1_u8) << cards) - 1_u8);
+
+        let modular = count_cards(Modulo(PRIME), adversarial(cards)).unwrap();
+        assert_eq!(BigUint::from(modular), exact % PRIME);
+    }
+}
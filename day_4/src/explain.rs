@@ -0,0 +1,113 @@
+//! Explanation of the copy cascade: which cards the copies of every card came from
+
+use std::cmp::Reverse;
+
+use crate::{
+    card::Card,
+    cascade::{Arithmetic, Overflow},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CardCopies<C> {
+    pub id: u64,
+    /// Original card included
+    pub copies: C,
+    /// Ids of the cards which granted the copies along with their quantity
+    pub sources: Vec<(u64, C)>,
+    /// Copies of the following cards granted by all the copies of this card
+    pub granted: C,
+}
+
+/// Copies of every card counted by the `arithmetic`, which reports the overflow the same way
+/// as the cascade
+pub fn explain_cascade<A: Arithmetic>(
+    arithmetic: &A,
+    cards: &[Card],
+) -> Result<Vec<CardCopies<A::Count>>, Overflow> {
+    let mut explanation: Vec<CardCopies<A::Count>> = cards
+        .iter()
+        .map(|card| CardCopies {
+            id: card.id,
+            copies: arithmetic.one(),
+            sources: Vec::new(),
+            granted: arithmetic.zero(),
+        })
+        .collect();
+
+    for (i, card) in cards.iter().enumerate() {
+        let copies = explanation[i].copies.clone();
+        // Cards are never copied past the end of the table
+        let last = (i + card.matches()).min(cards.len() - 1);
+        let mut granted = arithmetic.zero();
+        for (j, copied) in explanation
+            .iter_mut()
+            .enumerate()
+            .take(last + 1)
+            .skip(i + 1)
+        {
+            let overflow = Overflow { card: j + 1 };
+            copied.copies = arithmetic.add(&copied.copies, &copies).ok_or(overflow)?;
+            copied.sources.push((card.id, copies.clone()));
+            granted = arithmetic.add(&granted, &copies).ok_or(overflow)?;
+        }
+        explanation[i].granted = granted;
+    }
+
+    Ok(explanation)
+}
+
+/// Cards which granted the most copies, the first of them goes first on a tie
+pub fn top_contributors<'a, A: Arithmetic>(
+    arithmetic: &A,
+    explanation: &'a [CardCopies<A::Count>],
+    quantity: usize,
+) -> Vec<&'a CardCopies<A::Count>>
+where
+    A::Count: Ord,
+{
+    let zero = arithmetic.zero();
+    let mut contributors: Vec<&'a CardCopies<A::Count>> = explanation
+        .iter()
+        .filter(|card| card.granted > zero)
+        .collect();
+    contributors.sort_by_key(|card| Reverse(&card.granted));
+    contributors.truncate(quantity);
+    contributors
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+
+    use super::*;
+    use crate::{
+        card::parse_valid_cards,
+        cascade::{count_cards, Checked, Exact},
+    };
+
+    /// Every card has 2 matches, so the copies grow exponentially
+    fn cascading_cards(quantity: usize) -> String {
+        (1..=quantity)
+            .map(|id| format!("Card {id}: 1 2 | 1 2\n"))
+            .collect()
+    }
+
+    #[test]
+    fn overflow_is_reported() {
+        let cards = parse_valid_cards(&cascading_cards(120)).unwrap();
+        assert!(explain_cascade(&Checked, &cards).is_err());
+
+        let explanation = explain_cascade(&Exact, &cards).unwrap();
+        let total = explanation
+            .iter()
+            .fold(BigUint::ZERO, |total, card| total + &card.copies);
+        assert_eq!(
+            total,
+            count_cards(Exact, cards.iter().map(Card::matches)).unwrap()
+        );
+        assert_eq!(
+            top_contributors(&Exact, &explanation, 1)[0].id,
+            cards.len() as u64 - 2
+        );
+    }
+}
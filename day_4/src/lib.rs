@@ -0,0 +1,4 @@
+pub mod card;
+pub mod cascade;
+pub mod explain;
+pub mod scoring;
@@ -1,38 +1,23 @@
-use std::{collections::HashSet, fs::read_to_string};
+use std::{env, error::Error, fs::read_to_string};
+
+use day_4::{card::parse_valid_cards, scoring::ScoringPolicy};
 
 const INPUT_FILE: &str = "input.txt";
 
-fn main() {
-    let points_sum = read_to_string(INPUT_FILE)
-        .unwrap()
-        .lines()
-        .map(|line| {
-            let (winning_numbers, numbers_we_have) = line
-                .split_once(':')
-                .unwrap()
-                .1
-                .trim()
-                .split_once('|')
-                .map(|(lhs, rhs)| {
-                    (
-                        lhs.trim()
-                            .split(' ')
-                            .filter(|n| !n.is_empty())
-                            .map(|n| n.parse::<u64>().unwrap())
-                            .collect::<HashSet<u64>>(),
-                        rhs.trim()
-                            .split(' ')
-                            .filter(|n| !n.is_empty())
-                            .map(|n| n.parse::<u64>().unwrap())
-                            .collect::<HashSet<u64>>(),
-                    )
-                })
-                .unwrap();
-            winning_numbers.intersection(&numbers_we_have).count() as u32
-        })
-        .filter(|matched_numbers| *matched_numbers > 0)
-        .fold(0, |res, matched_numbers| {
-            res + 2_u64.pow(matched_numbers - 1)
-        });
-    println!("{points_sum}");
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut policy = ScoringPolicy::default();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--scoring" => {
+                policy = args.next().ok_or("--scoring expects a policy")?.parse()?;
+            }
+            _ => return Err(format!("unknown argument \"{arg}\"").into()),
+        }
+    }
+
+    let cards = parse_valid_cards(&read_to_string(INPUT_FILE)?)?;
+    println!("{}", policy.total(&cards));
+
+    Ok(())
 }
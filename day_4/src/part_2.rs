@@ -1,65 +1,95 @@
-use std::{cell::Cell, cmp::min, collections::HashSet, fs::read_to_string};
+use std::{env, error::Error, fmt::Display, fs::read_to_string};
 
-use nom::{
-    bytes::complete::tag,
-    character::complete::{digit1, newline, space1},
-    combinator::map_res,
-    multi::separated_list1,
-    sequence::{preceded, separated_pair, terminated, tuple},
-    IResult,
+use day_4::{
+    card::{parse_valid_cards, Card},
+    cascade::{count_cards, Arithmetic, Checked, Exact, Modulo, Overflow},
+    explain::{explain_cascade, top_contributors},
 };
 
 const INPUT_FILE: &str = "input.txt";
+const TOP_CONTRIBUTORS_QUANTITY: usize = 5;
 
-fn parse_card_header(input: &str) -> IResult<&str, ()> {
-    let (input, _) = tuple((tag("Card"), space1, digit1, tag(":")))(input)?;
-    Ok((input.trim(), ()))
+/// How the quantities of cards are counted
+enum Counting {
+    Checked,
+    Exact,
+    Modulo(u64),
 }
 
-fn parse_card_lists(input: &str) -> IResult<&str, usize> {
-    separated_pair(
-        terminated(
-            separated_list1(space1, map_res(digit1, |s: &str| s.parse::<u64>())),
-            space1,
-        ),
-        tag("|"),
-        preceded(
-            space1,
-            separated_list1(space1, map_res(digit1, |s: &str| s.parse::<u64>())),
-        ),
-    )(input)
-    .map(|(s, (lhs, rhs))| {
-        let lhs: HashSet<u64> = HashSet::from_iter(lhs);
-        let rhs = HashSet::from_iter(rhs);
-        (s, lhs.intersection(&rhs).count())
-    })
-}
-
-fn parse_cards(input: &str) -> Vec<usize> {
-    separated_list1(newline, tuple((parse_card_header, parse_card_lists)))(input)
-        .unwrap()
-        .1
-        .into_iter()
-        .map(|i| i.1)
-        .collect()
+/// Prints the copies of every card, the top contributors and the total
+fn print_explanation<A: Arithmetic>(arithmetic: A, cards: &[Card]) -> Result<(), Overflow>
+where
+    A::Count: Display + Ord,
+{
+    let explanation = explain_cascade(&arithmetic, cards)?;
+    let one = arithmetic.one();
+    for card in &explanation {
+        let sources: Vec<String> = card
+            .sources
+            .iter()
+            .map(|(id, copies)| format!("{copies} from card {id}"))
+            .collect();
+        let copies = if card.copies == one { "copy" } else { "copies" };
+        if sources.is_empty() {
+            println!("Card {}: {} {copies} (original)", card.id, card.copies);
+        } else {
+            println!(
+                "Card {}: {} {copies} (original, {})",
+                card.id,
+                card.copies,
+                sources.join(", ")
+            );
+        }
+    }
+    println!("Top contributors:");
+    for card in top_contributors(&arithmetic, &explanation, TOP_CONTRIBUTORS_QUANTITY) {
+        println!("Card {}: granted {} copies", card.id, card.granted);
+    }
+    println!(
+        "{}",
+        count_cards(arithmetic, cards.iter().map(Card::matches))?
+    );
+    Ok(())
 }
 
-fn main() {
-    let cards: Vec<(usize, Cell<usize>)> = parse_cards(&read_to_string(INPUT_FILE).unwrap())
-        .into_iter()
-        .map(|i| (i, Cell::new(1)))
-        .collect();
-
-    let mut total_cards = 0;
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut explain = false;
+    let mut counting = Counting::Checked;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--explain" => explain = true,
+            "--exact" => counting = Counting::Exact,
+            "--modulo" => {
+                let modulus: u64 = args.next().ok_or("--modulo expects a number")?.parse()?;
+                if modulus < 2 {
+                    return Err(format!("modulus {modulus} must be at least 2").into());
+                }
+                counting = Counting::Modulo(modulus);
+            }
+            _ => return Err(format!("unknown argument \"{arg}\"").into()),
+        }
+    }
 
-    for (i, (winning_numbers, quantity)) in cards.iter().enumerate() {
-        total_cards += quantity.get();
+    let cards = parse_valid_cards(&read_to_string(INPUT_FILE)?)?;
 
-        for j in (i + 1)..=min(i + winning_numbers, cards.len()) {
-            let cell = cards[j].1.get();
-            cards[j].1.set(cell + quantity.get())
+    if explain {
+        match counting {
+            Counting::Checked => print_explanation(Checked, &cards)?,
+            Counting::Exact => print_explanation(Exact, &cards)?,
+            Counting::Modulo(_) => {
+                return Err("--explain ranks the contributors, which remainders can't do".into())
+            }
         }
+        return Ok(());
+    }
+
+    let matches = cards.iter().map(|card| card.matches());
+    match counting {
+        Counting::Checked => println!("{}", count_cards(Checked, matches)?),
+        Counting::Exact => println!("{}", count_cards(Exact, matches)?),
+        Counting::Modulo(modulus) => println!("{}", count_cards(Modulo(modulus), matches)?),
     }
 
-    println!("{total_cards}");
+    Ok(())
 }
@@ -0,0 +1,141 @@
+//! Policies scoring a card by the quantity of its matches
+//!
+//! A policy is written as `doubling`, `linear`, `fibonacci` or `table:S1,S2,...`, where `Sk`
+//! is the score of `k` matches. A card without matches is worth nothing under any policy.
+
+use std::{fmt, str::FromStr};
+
+use crate::card::Card;
+
+const TABLE_PREFIX: &str = "table:";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ScoringPolicy {
+    /// First match is worth 1 point, each following one doubles the score
+    #[default]
+    Doubling,
+    /// Each match is worth 1 point
+    Linear,
+    /// `k` matches are worth the `k`-th Fibonacci number: 1, 1, 2, 3, 5, ...
+    Fibonacci,
+    /// `k` matches are worth the `k`-th score, matches past the table get the last one
+    Table(Vec<u64>),
+}
+
+impl ScoringPolicy {
+    /// Score of a card, saturated at `u64::MAX`
+    pub fn score(&self, matches: usize) -> u64 {
+        if matches == 0 {
+            return 0;
+        }
+        match self {
+            ScoringPolicy::Doubling => 2_u64.saturating_pow(matches as u32 - 1),
+            ScoringPolicy::Linear => matches as u64,
+            ScoringPolicy::Fibonacci => {
+                let (mut previous, mut current) = (0_u64, 1_u64);
+                for _ in 1..matches {
+                    (previous, current) = (current, previous.saturating_add(current));
+                }
+                current
+            }
+            ScoringPolicy::Table(scores) => scores
+                .get(matches - 1)
+                .or(scores.last())
+                .copied()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Sum of the card scores, saturated the same way as the scores
+    pub fn total(&self, cards: &[Card]) -> u64 {
+        cards.iter().fold(0, |total, card| {
+            total.saturating_add(self.score(card.matches()))
+        })
+    }
+}
+
+impl fmt::Display for ScoringPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScoringPolicy::Doubling => f.write_str("doubling"),
+            ScoringPolicy::Linear => f.write_str("linear"),
+            ScoringPolicy::Fibonacci => f.write_str("fibonacci"),
+            ScoringPolicy::Table(scores) => {
+                let scores: Vec<String> = scores.iter().map(u64::to_string).collect();
+                write!(f, "{TABLE_PREFIX}{}", scores.join(","))
+            }
+        }
+    }
+}
+
+impl FromStr for ScoringPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "doubling" => Ok(ScoringPolicy::Doubling),
+            "linear" => Ok(ScoringPolicy::Linear),
+            "fibonacci" => Ok(ScoringPolicy::Fibonacci),
+            _ => match s.strip_prefix(TABLE_PREFIX) {
+                Some(scores) => scores
+                    .split(',')
+                    .map(|score| {
+                        score
+                            .trim()
+                            .parse()
+                            .map_err(|_| format!("invalid score \"{score}\" in the table \"{s}\""))
+                    })
+                    .collect::<Result<_, _>>()
+                    .map(ScoringPolicy::Table),
+                None => Err(format!(
+                    "unknown scoring policy \"{s}\", expected doubling, linear, fibonacci \
+                     or table:S1,S2,..."
+                )),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policies_score_matches() {
+        let scores = |policy: ScoringPolicy| (0..=6).map(|m| policy.score(m)).collect::<Vec<_>>();
+        assert_eq!(scores(ScoringPolicy::Doubling), [0, 1, 2, 4, 8, 16, 32]);
+        assert_eq!(scores(ScoringPolicy::Linear), [0, 1, 2, 3, 4, 5, 6]);
+        assert_eq!(scores(ScoringPolicy::Fibonacci), [0, 1, 1, 2, 3, 5, 8]);
+        assert_eq!(
+            scores("table:3,5,10".parse().unwrap()),
+            [0, 3, 5, 10, 10, 10, 10]
+        );
+    }
+
+    #[test]
+    fn scores_saturate() {
+        let numbers: Vec<u64> = (1..=69).collect();
+        let card = |id| Card {
+            id,
+            winning: numbers.clone(),
+            have: numbers.clone(),
+        };
+        let cards = [card(1), card(2)];
+        assert_eq!(ScoringPolicy::Doubling.score(69), u64::MAX);
+        assert_eq!(ScoringPolicy::Doubling.total(&cards), u64::MAX);
+        assert_eq!(
+            ScoringPolicy::Fibonacci.total(&cards),
+            2 * ScoringPolicy::Fibonacci.score(69)
+        );
+        assert_eq!(ScoringPolicy::Linear.total(&cards), 138);
+    }
+
+    #[test]
+    fn policies_round_trip() {
+        for policy in ["doubling", "linear", "fibonacci", "table:1,2,4"] {
+            assert_eq!(policy.parse::<ScoringPolicy>().unwrap().to_string(), policy);
+        }
+        assert!("table:1,x".parse::<ScoringPolicy>().is_err());
+        assert!("squares".parse::<ScoringPolicy>().is_err());
+    }
+}
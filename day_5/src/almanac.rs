@@ -0,0 +1,297 @@
+//! Almanac parsing shared by both parts, they only differ in the way the seeds are read
+
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fmt,
+    str::FromStr,
+};
+
+use nom::{
+    bytes::complete::tag,
+    character::complete::{alpha1, digit1, multispace0, newline, space1},
+    combinator::{all_consuming, map_res},
+    multi::{many0, many1, separated_list1},
+    sequence::{preceded, separated_pair, terminated, tuple},
+    IResult,
+};
+
+use crate::{
+    chain::{resolve_chain, ChainError, SEED_CATEGORY},
+    range_map::{DstRange, RangeMap, SrcRange},
+};
+
+pub type SrcKey<'a> = &'a str;
+pub type DstKey<'a> = &'a str;
+pub type Transformation<'a> = ((SrcKey<'a>, DstKey<'a>), Vec<MappingLine>);
+
+/// `destination source length` line of a map block
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MappingLine {
+    pub destination: i64,
+    pub source: i64,
+    pub length: i64,
+}
+
+/// How the numbers of the `seeds:` line are interpreted
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SeedMode {
+    /// Every number is a seed
+    Individual,
+    /// Numbers are pairs of the first seed and the quantity of seeds
+    Ranges,
+}
+
+#[derive(Debug)]
+pub struct Input<'a> {
+    pub initial_seeds: Vec<i64>,
+    pub transformations: HashMap<(SrcKey<'a>, DstKey<'a>), Vec<MappingLine>>,
+    /// Blocks given again after the first one with the same categories, which is kept
+    pub repeated_blocks: Vec<(SrcKey<'a>, DstKey<'a>)>,
+}
+
+impl MappingLine {
+    /// `None` if the ends of the ranges don't fit into `i64`
+    pub fn checked_ranges(&self) -> Option<(SrcRange, DstRange)> {
+        Some((
+            self.source..self.source.checked_add(self.length)?,
+            self.destination..self.destination.checked_add(self.length)?,
+        ))
+    }
+
+    /// Ranges of the line, which must not overflow
+    pub fn ranges(&self) -> (SrcRange, DstRange) {
+        (
+            self.source..self.source + self.length,
+            self.destination..self.destination + self.length,
+        )
+    }
+}
+
+impl FromStr for SeedMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "individual" => Ok(SeedMode::Individual),
+            "ranges" => Ok(SeedMode::Ranges),
+            _ => Err(format!(
+                "unknown seed mode \"{s}\", expected \"individual\" or \"ranges\""
+            )),
+        }
+    }
+}
+
+impl Input<'_> {
+    /// Seeds of the `seeds:` line, which must not overflow `i64`
+    pub fn seed_ranges(&self, mode: SeedMode) -> Result<Vec<SrcRange>, String> {
+        match mode {
+            SeedMode::Individual => self
+                .initial_seeds
+                .iter()
+                .map(|seed| {
+                    Ok(*seed
+                        ..seed
+                            .checked_add(1)
+                            .ok_or_else(|| format!("seed {seed} overflows i64"))?)
+                })
+                .collect(),
+            SeedMode::Ranges if !self.initial_seeds.len().is_multiple_of(2) => Err(format!(
+                "seed ranges expect pairs of numbers, got {} numbers",
+                self.initial_seeds.len()
+            )),
+            SeedMode::Ranges => self
+                .initial_seeds
+                .chunks(2)
+                .map(|chunk| {
+                    Ok(chunk[0]..chunk[0].checked_add(chunk[1]).ok_or_else(|| {
+                        format!("seed range {} {} overflows i64", chunk[0], chunk[1])
+                    })?)
+                })
+                .collect(),
+        }
+    }
+
+    /// Links between the categories, the repeated blocks are included for the chain to report
+    pub fn links(&self) -> impl Iterator<Item = (SrcKey<'_>, DstKey<'_>)> {
+        self.transformations
+            .keys()
+            .chain(&self.repeated_blocks)
+            .copied()
+    }
+
+    /// Single map converting the `source` category into the `target` one
+    pub fn collapse(&self, source: &str, target: &str) -> Result<RangeMap, ChainError> {
+        let chain = resolve_chain(self.links(), source, target)?;
+        Ok(chain.into_iter().fold(RangeMap::default(), |almanac, key| {
+            almanac.compose(&RangeMap::new(
+                self.transformations[&key].iter().map(MappingLine::ranges),
+            ))
+        }))
+    }
+
+    /// Blocks along the links starting from the seeds, the unreachable ones go after them in
+    /// the alphabetical order
+    pub fn blocks(&self) -> Vec<((SrcKey<'_>, DstKey<'_>), &[MappingLine])> {
+        let mut keys: Vec<(SrcKey, DstKey)> = self.transformations.keys().copied().collect();
+        keys.sort_unstable();
+
+        let mut ordered = Vec::with_capacity(keys.len());
+        let mut current = SEED_CATEGORY;
+        while let Some(position) = keys.iter().position(|(src, _)| *src == current) {
+            let key = keys.remove(position);
+            ordered.push(key);
+            current = key.1;
+        }
+        ordered.extend(keys);
+
+        ordered
+            .into_iter()
+            .map(|key| (key, self.transformations[&key].as_slice()))
+            .collect()
+    }
+}
+
+/// Canonical almanac text: blocks in the chain order separated by a blank line
+impl fmt::Display for Input<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seeds: Vec<String> = self.initial_seeds.iter().map(i64::to_string).collect();
+        writeln!(f, "seeds: {}", seeds.join(" "))?;
+        for ((src_key, dst_key), lines) in self.blocks() {
+            write!(f, "\n{src_key}-to-{dst_key} map:\n")?;
+            for line in lines {
+                writeln!(f, "{} {} {}", line.destination, line.source, line.length)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn parse_number(input: &str) -> IResult<&str, i64> {
+    map_res(digit1, |s: &str| s.parse::<i64>())(input)
+}
+
+fn parse_initial_seeds(input: &str) -> IResult<&str, Vec<i64>> {
+    terminated(
+        preceded(tag("seeds: "), separated_list1(space1, parse_number)),
+        newline,
+    )(input)
+}
+
+fn parse_mapping_line(input: &str) -> IResult<&str, MappingLine> {
+    let (input, (destination, source, length)) = tuple((
+        parse_number,
+        preceded(space1, parse_number),
+        preceded(space1, parse_number),
+    ))(input)?;
+
+    Ok((
+        input,
+        MappingLine {
+            destination,
+            source,
+            length,
+        },
+    ))
+}
+
+/// Block may have no lines, it maps every value to itself then
+fn parse_transformation_step(input: &str) -> IResult<&str, Transformation<'_>> {
+    tuple((
+        terminated(separated_pair(alpha1, tag("-to-"), alpha1), tag(" map:")),
+        many0(preceded(newline, parse_mapping_line)),
+    ))(input)
+}
+
+pub fn parse_input(input: &str) -> IResult<&str, Input<'_>> {
+    let (input, (initial_seeds, transformations)) = all_consuming(terminated(
+        tuple((
+            parse_initial_seeds,
+            many1(preceded(multispace0, parse_transformation_step)),
+        )),
+        multispace0,
+    ))(input)?;
+
+    let mut blocks = HashMap::with_capacity(transformations.len());
+    let mut repeated_blocks = Vec::new();
+    for (key, lines) in transformations {
+        match blocks.entry(key) {
+            Entry::Vacant(entry) => {
+                entry.insert(lines);
+            }
+            Entry::Occupied(_) => repeated_blocks.push(key),
+        }
+    }
+
+    Ok((
+        input,
+        Input {
+            initial_seeds,
+            transformations: blocks,
+            repeated_blocks,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain::LOCATION_CATEGORY;
+
+    #[test]
+    fn repeated_block_is_kept_once_and_reported() {
+        const ALMANAC: &str = "\
+seeds: 1
+
+seed-to-soil map:
+5 0 10
+
+seed-to-water map:
+
+seed-to-soil map:
+7 0 10
+";
+        let input = parse_input(ALMANAC).unwrap().1;
+        assert_eq!(input.repeated_blocks, vec![("seed", "soil")]);
+        assert_eq!(
+            input.transformations[&("seed", "soil")],
+            vec![MappingLine {
+                destination: 5,
+                source: 0,
+                length: 10
+            }]
+        );
+        assert_eq!(
+            input.collapse(SEED_CATEGORY, "water"),
+            Err(ChainError::DuplicateMap {
+                src: "seed".to_owned(),
+                dst: "soil".to_owned()
+            })
+        );
+
+        let input = parse_input("seeds: 1\n\nseed-to-soil map:\n\nseed-to-water map:\n5 0 10\n")
+            .unwrap()
+            .1;
+        assert_eq!(
+            input.collapse(SEED_CATEGORY, "water"),
+            Ok(RangeMap::new([(0..10, 5..15)]))
+        );
+        assert!(input.collapse(SEED_CATEGORY, LOCATION_CATEGORY).is_err());
+    }
+
+    #[test]
+    fn overflowing_seeds_are_reported() {
+        let input = parse_input("seeds: 9223372036854775807 5\n\nseed-to-soil map:\n")
+            .unwrap()
+            .1;
+        assert!(input.seed_ranges(SeedMode::Individual).is_err());
+        assert!(input.seed_ranges(SeedMode::Ranges).is_err());
+
+        let input = parse_input("seeds: 9223372036854775806 1 0 2\n\nseed-to-soil map:\n")
+            .unwrap()
+            .1;
+        assert_eq!(
+            input.seed_ranges(SeedMode::Ranges),
+            Ok(vec![9223372036854775806..i64::MAX, 0..2])
+        );
+    }
+}
@@ -0,0 +1,24 @@
+//! Brute-force oracle mapping every seed on its own by scanning the mapping lines, the way
+//! the part 1 used to do
+
+use crate::range_map::{DstRange, SrcRange};
+
+/// Mapping lines of every stage of the chain in the almanac order
+pub type Chain = [Vec<(SrcRange, DstRange)>];
+
+pub fn map_seed(chain: &Chain, seed: i64) -> i64 {
+    chain.iter().fold(seed, |id, mappings| {
+        mappings
+            .iter()
+            .find(|(src, _)| src.contains(&id))
+            .map_or(id, |(src, dst)| dst.start + (id - src.start))
+    })
+}
+
+/// Locations of all the seeds of the ranges, in the seeds order
+pub fn map_seed_ranges(chain: &Chain, seed_ranges: &[SrcRange]) -> Vec<i64> {
+    seed_ranges
+        .iter()
+        .flat_map(|range| range.clone().map(|seed| map_seed(chain, seed)))
+        .collect()
+}
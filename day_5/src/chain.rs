@@ -0,0 +1,247 @@
+//! Categories of the almanac form a graph where every `src-to-dst` map is a link. The chain
+//! between two categories is the shortest path over the links, so it doesn't depend on the
+//! order the maps are written in. A category may be converted by several maps.
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    error::Error,
+    fmt,
+};
+
+pub const SEED_CATEGORY: &str = "seed";
+pub const LOCATION_CATEGORY: &str = "location";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChainError {
+    /// Same `src-to-dst` map is given more than once
+    DuplicateMap { src: String, dst: String },
+    /// No path leads from the `source` to the `target`, `dead_ends` are the categories
+    /// reachable from the `source` which no map converts
+    MissingLink {
+        source: String,
+        target: String,
+        dead_ends: Vec<String>,
+    },
+    /// Cycle reachable from the source category, the first category is repeated at the end
+    Cycle(Vec<String>),
+}
+
+impl fmt::Display for ChainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChainError::DuplicateMap { src, dst } => {
+                write!(f, "map \"{src}-to-{dst}\" is given more than once")
+            }
+            ChainError::MissingLink {
+                source,
+                target,
+                dead_ends,
+            } => write!(
+                f,
+                "no maps lead from \"{source}\" to \"{target}\", nothing converts {}",
+                dead_ends
+                    .iter()
+                    .map(|category| format!("\"{category}\""))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            ChainError::Cycle(categories) => {
+                write!(f, "maps form a cycle: {}", categories.join(" -> "))
+            }
+        }
+    }
+}
+
+impl Error for ChainError {}
+
+/// Depth-first search of a cycle reachable from the category, `path` leads to the category
+fn find_cycle<'a>(
+    next_categories: &HashMap<&'a str, Vec<&'a str>>,
+    category: &'a str,
+    path: &mut Vec<&'a str>,
+    finished: &mut HashSet<&'a str>,
+) -> Option<Vec<String>> {
+    if let Some(start) = path.iter().position(|visited| *visited == category) {
+        let mut cycle: Vec<String> = path[start..]
+            .iter()
+            .map(|visited| (*visited).to_owned())
+            .collect();
+        cycle.push(category.to_owned());
+        return Some(cycle);
+    }
+    if finished.contains(category) {
+        return None;
+    }
+
+    path.push(category);
+    for next in next_categories.get(category).into_iter().flatten() {
+        if let Some(cycle) = find_cycle(next_categories, next, path, finished) {
+            return Some(cycle);
+        }
+    }
+    path.pop();
+    finished.insert(category);
+    None
+}
+
+/// Links leading from the `source` category to the `target` one, empty if they're the same.
+/// Almanac containing a cycle reachable from the `source` is rejected even if the `target`
+/// is reachable as well
+pub fn resolve_chain<'a>(
+    links: impl IntoIterator<Item = (&'a str, &'a str)>,
+    source: &'a str,
+    target: &str,
+) -> Result<Vec<(&'a str, &'a str)>, ChainError> {
+    let mut next_categories: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (src, dst) in links {
+        let next = next_categories.entry(src).or_default();
+        if next.contains(&dst) {
+            return Err(ChainError::DuplicateMap {
+                src: src.to_owned(),
+                dst: dst.to_owned(),
+            });
+        }
+        next.push(dst);
+    }
+    // Ties between the paths of the same length are broken alphabetically
+    for next in next_categories.values_mut() {
+        next.sort_unstable();
+    }
+
+    if let Some(cycle) = find_cycle(
+        &next_categories,
+        source,
+        &mut Vec::new(),
+        &mut HashSet::new(),
+    ) {
+        return Err(ChainError::Cycle(cycle));
+    }
+
+    // Breadth-first search remembering where every category is reached from
+    let mut previous: HashMap<&str, &str> = HashMap::new();
+    let mut visited: Vec<&str> = vec![source];
+    let mut pending = VecDeque::from([source]);
+    while let Some(category) = pending.pop_front() {
+        if category == target {
+            break;
+        }
+        for &next in next_categories.get(category).into_iter().flatten() {
+            if !visited.contains(&next) {
+                visited.push(next);
+                previous.insert(next, category);
+                pending.push_back(next);
+            }
+        }
+    }
+
+    if source != target && !previous.contains_key(target) {
+        let mut dead_ends: Vec<String> = visited
+            .into_iter()
+            .filter(|category| !next_categories.contains_key(category))
+            .map(str::to_owned)
+            .collect();
+        dead_ends.sort_unstable();
+        return Err(ChainError::MissingLink {
+            source: source.to_owned(),
+            target: target.to_owned(),
+            dead_ends,
+        });
+    }
+
+    let mut chain = Vec::new();
+    let mut current = target;
+    while current != source {
+        let (&dst, &src) = previous.get_key_value(current).unwrap();
+        chain.push((src, dst));
+        current = src;
+    }
+    chain.reverse();
+
+    Ok(chain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINKS: [(&str, &str); 4] = [
+        ("water", "light"),
+        ("seed", "soil"),
+        ("fertilizer", "water"),
+        ("soil", "fertilizer"),
+    ];
+
+    #[test]
+    fn chain_does_not_depend_on_maps_order() {
+        assert_eq!(
+            resolve_chain(LINKS, "seed", "light"),
+            Ok(vec![
+                ("seed", "soil"),
+                ("soil", "fertilizer"),
+                ("fertilizer", "water"),
+                ("water", "light"),
+            ])
+        );
+        assert_eq!(
+            resolve_chain(LINKS, "soil", "water"),
+            Ok(vec![("soil", "fertilizer"), ("fertilizer", "water")])
+        );
+        assert_eq!(resolve_chain(LINKS, "soil", "soil"), Ok(vec![]));
+    }
+
+    #[test]
+    fn missing_link_is_reported() {
+        assert_eq!(
+            resolve_chain(LINKS, "seed", "location"),
+            Err(ChainError::MissingLink {
+                source: "seed".to_owned(),
+                target: "location".to_owned(),
+                dead_ends: vec!["light".to_owned()]
+            })
+        );
+    }
+
+    #[test]
+    fn cycle_is_reported() {
+        let links = LINKS.into_iter().chain([("light", "soil")]);
+        assert_eq!(
+            resolve_chain(links, "seed", "location"),
+            Err(ChainError::Cycle(
+                ["soil", "fertilizer", "water", "light", "soil"]
+                    .map(String::from)
+                    .to_vec()
+            ))
+        );
+    }
+
+    #[test]
+    fn category_may_have_several_maps() {
+        let links = LINKS
+            .into_iter()
+            .chain([("seed", "water"), ("soil", "light")]);
+        assert_eq!(
+            resolve_chain(links.clone(), "seed", "water"),
+            Ok(vec![("seed", "water")])
+        );
+        assert_eq!(
+            resolve_chain(links.clone(), "seed", "light"),
+            Ok(vec![("seed", "soil"), ("soil", "light")])
+        );
+        assert_eq!(
+            resolve_chain(links, "soil", "fertilizer"),
+            Ok(vec![("soil", "fertilizer")])
+        );
+    }
+
+    #[test]
+    fn duplicate_map_is_reported() {
+        let links = LINKS.into_iter().chain([("soil", "fertilizer")]);
+        assert_eq!(
+            resolve_chain(links, "seed", "water"),
+            Err(ChainError::DuplicateMap {
+                src: "soil".to_owned(),
+                dst: "fertilizer".to_owned()
+            })
+        );
+    }
+}
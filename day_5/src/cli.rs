@@ -0,0 +1,157 @@
+//! Command line shared by both parts, which only differ in the default seed mode
+
+use std::{env, error::Error, fs};
+
+use crate::{
+    almanac::{parse_input, SeedMode},
+    chain::{LOCATION_CATEGORY, SEED_CATEGORY},
+    range_map::{intersection, SrcRange},
+    timeline::render_svg,
+    trace::{trace, trace_ranges, Values},
+    validate::{validate, Issue},
+};
+
+const INPUT_FILE: &str = "input.txt";
+
+enum Query {
+    MinLocation,
+    /// Seed ranges reaching the values below the given one
+    Below(i64),
+    /// Seed behind the minimum location
+    MinSeed,
+    /// Every hop of the seeds through the chain
+    Trace(SrcRange),
+    /// SVG timeline of the seed ranges fragmentation
+    Svg,
+}
+
+/// Single seed or non-empty `start..end` seeds
+fn parse_seeds(s: &str) -> Result<SrcRange, Box<dyn Error>> {
+    let seeds = match s.split_once("..") {
+        Some((start, end)) => start.parse()?..end.parse()?,
+        None => {
+            let seed: i64 = s.parse()?;
+            seed..seed
+                .checked_add(1)
+                .ok_or_else(|| format!("seed {seed} is too large to trace"))?
+        }
+    };
+    if seeds.is_empty() {
+        return Err(format!("seeds {s} are an empty range").into());
+    }
+    Ok(seeds)
+}
+
+pub fn run(mut seed_mode: SeedMode) -> Result<(), Box<dyn Error>> {
+    let mut query = Query::MinLocation;
+    let mut source = SEED_CATEGORY.to_owned();
+    let mut target = LOCATION_CATEGORY.to_owned();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seeds" => seed_mode = args.next().ok_or("--seeds expects a mode")?.parse()?,
+            "--from" => source = args.next().ok_or("--from expects a category")?,
+            "--to" => target = args.next().ok_or("--to expects a category")?,
+            "--below" => {
+                query = Query::Below(args.next().ok_or("--below expects a value")?.parse()?)
+            }
+            "--min-seed" => query = Query::MinSeed,
+            "--svg" => query = Query::Svg,
+            "--trace" => {
+                query = Query::Trace(parse_seeds(&args.next().ok_or("--trace expects seeds")?)?)
+            }
+            _ => return Err(format!("unknown argument \"{arg}\"").into()),
+        }
+    }
+
+    let input_file_content = fs::read_to_string(INPUT_FILE)?;
+    let input = parse_input(&input_file_content)
+        .map_err(|e| e.to_owned())?
+        .1;
+    if let Some(issue) = validate(&input)
+        .into_iter()
+        .find(|issue| matches!(issue, Issue::Overflow { .. } | Issue::RepeatedBlock { .. }))
+    {
+        return Err(issue.into());
+    }
+
+    if let Query::Trace(seeds) = query {
+        println!("{source} {}", Values(&seeds));
+        for hop in trace(&input, seeds, &source, &target)? {
+            print!("{hop}");
+        }
+        return Ok(());
+    }
+
+    let seed_ranges = input.seed_ranges(seed_mode)?;
+
+    if let Query::Svg = query {
+        let hops = trace_ranges(&input, seed_ranges.clone(), &source, &target)?;
+        print!("{}", render_svg(&source, &seed_ranges, &hops));
+        return Ok(());
+    }
+
+    // Whole chain collapses into a single map before any seed is processed
+    let almanac = input.collapse(&source, &target)?;
+
+    if let Query::Below(value) = query {
+        let preimage = almanac.preimage_range(i64::MIN..value);
+        let mut seeds: Vec<SrcRange> = seed_ranges
+            .iter()
+            .flat_map(|initial_seeds| {
+                preimage
+                    .iter()
+                    .map(|seeds| intersection(seeds, initial_seeds))
+            })
+            .filter(|seeds| !seeds.is_empty())
+            .collect();
+        seeds.sort_unstable_by_key(|seeds| seeds.start);
+        for seeds in seeds {
+            println!("{}", Values(&seeds));
+        }
+        return Ok(());
+    }
+
+    let min = almanac
+        .map_ranges(seed_ranges.iter().cloned())
+        .into_iter()
+        .map(|item| item.start)
+        .min()
+        .ok_or("there are no seeds")?;
+
+    if let Query::MinSeed = query {
+        let seed = almanac
+            .preimage(min)
+            .into_iter()
+            .find(|seed| seed_ranges.iter().any(|seeds| seeds.contains(seed)))
+            .ok_or("minimum has no seed behind it")?;
+        println!("seed {seed} reaches {target} {min}");
+        return Ok(());
+    }
+
+    println!("{min}");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeds_are_parsed() {
+        assert_eq!(parse_seeds("79").unwrap(), 79..80);
+        assert_eq!(parse_seeds("5..10").unwrap(), 5..10);
+        assert_eq!(parse_seeds("-3..-1").unwrap(), -3..-1);
+        for s in [
+            "10..5",
+            "5..5",
+            "9223372036854775807",
+            "x",
+            "1..",
+            "1..2..3",
+        ] {
+            assert!(parse_seeds(s).is_err(), "{s}");
+        }
+    }
+}
@@ -0,0 +1,8 @@
+pub mod almanac;
+pub mod brute_force;
+pub mod chain;
+pub mod cli;
+pub mod range_map;
+pub mod timeline;
+pub mod trace;
+pub mod validate;
@@ -0,0 +1,30 @@
+use std::{error::Error, fs::read_to_string};
+
+use day_5::{
+    almanac::parse_input,
+    validate::{normalize, validate, Issue},
+};
+
+const INPUT_FILE: &str = "input.txt";
+
+/// Reports the almanac issues and prints the canonical almanac giving the same answers
+fn main() -> Result<(), Box<dyn Error>> {
+    let input_file_content = read_to_string(INPUT_FILE)?;
+    let input = parse_input(&input_file_content)
+        .map_err(|e| e.to_owned())?
+        .1;
+
+    let issues = validate(&input);
+    for issue in &issues {
+        eprintln!("{issue}");
+    }
+    if let Some(issue) = issues
+        .into_iter()
+        .find(|issue| matches!(issue, Issue::Overflow { .. } | Issue::RepeatedBlock { .. }))
+    {
+        return Err(issue.into());
+    }
+
+    print!("{}", normalize(&input));
+    Ok(())
+}
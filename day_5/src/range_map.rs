@@ -0,0 +1,342 @@
+//! Piecewise-linear maps of integers: values inside a segment are shifted by its offset,
+//! other values are mapped to themselves
+
+use std::ops::Range;
+
+pub type SrcRange = Range<i64>;
+pub type DstRange = Range<i64>;
+
+/// Common part of the ranges, empty if they don't overlap
+pub fn intersection(a: &Range<i64>, b: &Range<i64>) -> Range<i64> {
+    a.start.max(b.start)..a.end.min(b.end)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Segment {
+    pub source: SrcRange,
+    pub offset: i64,
+}
+
+/// Segments are sorted, non-empty, don't overlap and have non-zero offsets. Adjacent segments
+/// have different offsets, so equal maps have equal segments
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RangeMap {
+    segments: Vec<Segment>,
+}
+
+impl RangeMap {
+    /// Mapping lines as in the almanac, the first line containing a value maps it
+    pub fn new(mappings: impl IntoIterator<Item = (SrcRange, DstRange)>) -> Self {
+        let mut covered: Vec<SrcRange> = Vec::new();
+        let mut segments = Vec::new();
+
+        for (source, destination) in mappings {
+            let offset = destination.start - source.start;
+            let mut uncovered = vec![source];
+            for covered in &covered {
+                uncovered = uncovered
+                    .into_iter()
+                    .flat_map(|range| {
+                        [
+                            range.start..range.end.min(covered.start),
+                            range.start.max(covered.end)..range.end,
+                        ]
+                    })
+                    .filter(|range| !range.is_empty())
+                    .collect();
+            }
+            for source in uncovered {
+                covered.push(source.clone());
+                segments.push(Segment { source, offset });
+            }
+        }
+
+        RangeMap::from_segments(segments)
+    }
+
+    /// Sorts the non-overlapping segments and merges the adjacent ones with equal offsets
+    fn from_segments(mut segments: Vec<Segment>) -> Self {
+        segments.retain(|segment| !segment.source.is_empty() && segment.offset != 0);
+        segments.sort_unstable_by_key(|segment| segment.source.start);
+
+        let mut merged: Vec<Segment> = Vec::with_capacity(segments.len());
+        for segment in segments {
+            match merged.last_mut() {
+                Some(last)
+                    if last.source.end == segment.source.start && last.offset == segment.offset =>
+                {
+                    last.source.end = segment.source.end;
+                }
+                _ => merged.push(segment),
+            }
+        }
+
+        RangeMap { segments: merged }
+    }
+
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Index of the first segment ending after the value
+    fn first_segment_after(&self, value: i64) -> usize {
+        self.segments
+            .partition_point(|segment| segment.source.end <= value)
+    }
+
+    pub fn get(&self, value: i64) -> i64 {
+        match self.segments.get(self.first_segment_after(value)) {
+            Some(segment) if segment.source.contains(&value) => value + segment.offset,
+            _ => value,
+        }
+    }
+
+    /// Splits the range by the segments, the parts between them have zero offset
+    fn pieces(&self, range: SrcRange) -> Vec<Segment> {
+        let mut pieces = Vec::new();
+        let mut start = range.start;
+
+        for segment in &self.segments[self.first_segment_after(range.start)..] {
+            if start >= range.end || segment.source.start >= range.end {
+                break;
+            }
+            if start < segment.source.start {
+                pieces.push(Segment {
+                    source: start..segment.source.start,
+                    offset: 0,
+                });
+            }
+            let end = segment.source.end.min(range.end);
+            pieces.push(Segment {
+                source: start.max(segment.source.start)..end,
+                offset: segment.offset,
+            });
+            start = end;
+        }
+        if start < range.end {
+            pieces.push(Segment {
+                source: start..range.end,
+                offset: 0,
+            });
+        }
+
+        pieces
+    }
+
+    /// Image of the range, one range per segment or gap it crosses
+    pub fn map_range(&self, range: SrcRange) -> Vec<DstRange> {
+        self.pieces(range)
+            .into_iter()
+            .map(|piece| piece.source.start + piece.offset..piece.source.end + piece.offset)
+            .collect()
+    }
+
+    pub fn map_ranges(&self, ranges: impl IntoIterator<Item = SrcRange>) -> Vec<DstRange> {
+        ranges
+            .into_iter()
+            .flat_map(|range| self.map_range(range))
+            .collect()
+    }
+
+    /// Values mapped into the range, sorted and merged when adjacent
+    pub fn preimage_range(&self, range: DstRange) -> Vec<SrcRange> {
+        let mut preimage: Vec<SrcRange> = self
+            .segments
+            .iter()
+            .map(|segment| {
+                let shifted = range.start.saturating_sub(segment.offset)
+                    ..range.end.saturating_sub(segment.offset);
+                intersection(&segment.source, &shifted)
+            })
+            .filter(|source| !source.is_empty())
+            .collect();
+        // Values outside of the segments are their own preimage
+        preimage.extend(
+            self.pieces(range)
+                .into_iter()
+                .filter(|piece| piece.offset == 0)
+                .map(|piece| piece.source),
+        );
+        preimage.sort_unstable_by_key(|source| source.start);
+
+        let mut merged: Vec<SrcRange> = Vec::with_capacity(preimage.len());
+        for source in preimage {
+            match merged.last_mut() {
+                Some(last) if last.end == source.start => last.end = source.end,
+                _ => merged.push(source),
+            }
+        }
+        merged
+    }
+
+    /// Values mapped to the value in the ascending order
+    pub fn preimage(&self, value: i64) -> Vec<i64> {
+        self.preimage_range(value..value + 1)
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    /// Map applying `self` first and `next` then
+    pub fn compose(&self, next: &RangeMap) -> RangeMap {
+        let mut segments = Vec::new();
+
+        // Values moved by `self`, then by `next` wherever they land
+        for segment in &self.segments {
+            let image = segment.source.start + segment.offset..segment.source.end + segment.offset;
+            segments.extend(next.pieces(image).into_iter().map(|piece| Segment {
+                source: piece.source.start - segment.offset..piece.source.end - segment.offset,
+                offset: segment.offset + piece.offset,
+            }));
+        }
+        // Values kept by `self` and moved by `next`
+        for segment in &next.segments {
+            segments.extend(
+                self.pieces(segment.source.clone())
+                    .into_iter()
+                    .filter(|piece| piece.offset == 0)
+                    .map(|piece| Segment {
+                        source: piece.source,
+                        offset: segment.offset,
+                    }),
+            );
+        }
+
+        RangeMap::from_segments(segments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::brute_force::{map_seed, map_seed_ranges};
+
+    fn seed_to_soil() -> RangeMap {
+        RangeMap::new([(98..100, 50..52), (50..98, 52..100)])
+    }
+
+    fn soil_to_fertilizer() -> RangeMap {
+        RangeMap::new([(15..52, 0..37), (52..54, 37..39), (0..15, 39..54)])
+    }
+
+    #[test]
+    fn lookup() {
+        let map = seed_to_soil();
+        assert_eq!(
+            [79, 14, 55, 13, 98, 99, 100].map(|seed| map.get(seed)),
+            [81, 14, 57, 13, 50, 51, 100]
+        );
+    }
+
+    #[test]
+    fn first_mapping_line_wins() {
+        let map = RangeMap::new([(0..10, 100..110), (5..15, 205..215)]);
+        assert_eq!(
+            [4, 9, 10, 14].map(|value| map.get(value)),
+            [104, 109, 210, 214]
+        );
+    }
+
+    #[test]
+    fn range_covering_segments_is_split() {
+        assert_eq!(
+            seed_to_soil().map_range(40..110),
+            vec![40..50, 52..100, 50..52, 100..110]
+        );
+        assert_eq!(seed_to_soil().map_range(60..60), vec![]);
+    }
+
+    #[test]
+    fn range_covering_whole_mapping_is_split_in_three() {
+        let map = RangeMap::new([(10..20, 110..120)]);
+        assert_eq!(map.map_range(5..25), vec![5..10, 110..120, 20..25]);
+    }
+
+    #[test]
+    fn preimage_of_value_and_range() {
+        let map = seed_to_soil();
+        assert_eq!(map.preimage(50), vec![98]);
+        assert_eq!(map.preimage(52), vec![50]);
+        assert_eq!(map.preimage(49), vec![49]);
+        assert_eq!(map.preimage(99), vec![97]);
+        // Value moved away isn't its own preimage
+        assert_eq!(
+            RangeMap::new([(0..10, 100..110)]).preimage(5),
+            Vec::<i64>::new()
+        );
+        assert_eq!(map.preimage_range(45..55), vec![45..53, 98..100]);
+    }
+
+    #[test]
+    fn composition_matches_sequential_lookup() {
+        let (first, second) = (seed_to_soil(), soil_to_fertilizer());
+        let composed = first.compose(&second);
+        for value in -5..120 {
+            assert_eq!(composed.get(value), second.get(first.get(value)), "{value}");
+        }
+    }
+
+    fn mapping() -> impl Strategy<Value = (SrcRange, DstRange)> {
+        (0..60_i64, 1..20_i64, 0..60_i64)
+            .prop_map(|(src, length, dst)| (src..src + length, dst..dst + length))
+    }
+
+    /// Stages may contain overlapping mapping lines, the first one wins then
+    fn chain() -> impl Strategy<Value = Vec<Vec<(SrcRange, DstRange)>>> {
+        prop::collection::vec(prop::collection::vec(mapping(), 0..6), 1..6)
+    }
+
+    fn seed_ranges() -> impl Strategy<Value = Vec<SrcRange>> {
+        prop::collection::vec(
+            (0..80_i64, 0..20_i64).prop_map(|(start, length)| start..start + length),
+            1..5,
+        )
+    }
+
+    fn sorted_values(ranges: Vec<Range<i64>>) -> Vec<i64> {
+        let mut values: Vec<i64> = ranges.into_iter().flatten().collect();
+        values.sort_unstable();
+        values
+    }
+
+    proptest! {
+        #[test]
+        fn ranges_match_brute_force(chain in chain(), seed_ranges in seed_ranges()) {
+            let mut expected = map_seed_ranges(&chain, &seed_ranges);
+            expected.sort_unstable();
+
+            let stage_by_stage = chain.iter().fold(seed_ranges.clone(), |ranges, mappings| {
+                RangeMap::new(mappings.iter().cloned()).map_ranges(ranges)
+            });
+            prop_assert_eq!(sorted_values(stage_by_stage), expected.clone());
+
+            let collapsed = chain.iter().fold(RangeMap::default(), |map, mappings| {
+                map.compose(&RangeMap::new(mappings.iter().cloned()))
+            });
+            prop_assert_eq!(sorted_values(collapsed.map_ranges(seed_ranges)), expected);
+        }
+
+        #[test]
+        fn lookup_matches_brute_force(chain in chain(), seed in -5..100_i64) {
+            let collapsed = chain.iter().fold(RangeMap::default(), |map, mappings| {
+                map.compose(&RangeMap::new(mappings.iter().cloned()))
+            });
+            prop_assert_eq!(collapsed.get(seed), map_seed(&chain, seed));
+        }
+
+        #[test]
+        fn preimage_matches_brute_force(chain in chain(), location in 0..80_i64) {
+            let collapsed = chain.iter().fold(RangeMap::default(), |map, mappings| {
+                map.compose(&RangeMap::new(mappings.iter().cloned()))
+            });
+            // Segments lie within 0..80 and shift by less than 60, so other seeds keep their
+            // values and can't reach the location
+            let expected: Vec<i64> = (-60..140)
+                .filter(|seed| map_seed(&chain, *seed) == location)
+                .collect();
+            prop_assert_eq!(collapsed.preimage(location), expected);
+        }
+    }
+}
@@ -0,0 +1,212 @@
+//! SVG timeline of the range fragmentation: every category is a horizontal number line with
+//! its ranges drawn as bars, arrows lead from each fragment to the place it's shifted to
+
+use std::{fmt::Write, ops::Range};
+
+use crate::{
+    range_map::SrcRange,
+    trace::{Fragment, Hop, Values},
+};
+
+const WIDTH: f64 = 1200.0;
+const LABEL_WIDTH: f64 = 200.0;
+const MARGIN: f64 = 20.0;
+const ROW_HEIGHT: f64 = 90.0;
+const BAR_HEIGHT: f64 = 12.0;
+/// Bars narrower than that are widened to stay visible
+const MIN_BAR_WIDTH: f64 = 1.0;
+
+const SVG_STYLE: &str = "\
+text { font-family: monospace; font-size: 12px; fill: #333333; }
+.axis { stroke: #999999; stroke-width: 1; }
+.bar { fill: #3366cc; }
+.split { stroke: #ffffff; stroke-width: 1; }
+.shifted { stroke: #cc6633; stroke-width: 1; marker-end: url(#arrow); }
+.unmapped { stroke: #aaaaaa; stroke-width: 1; stroke-dasharray: 3 3; marker-end: url(#arrow); }";
+
+/// Linear mapping of the values onto the plot width
+struct Scale {
+    min: i64,
+    max: i64,
+}
+
+impl Scale {
+    fn x(&self, value: i64) -> f64 {
+        let span = (self.max - self.min).max(1) as f64;
+        LABEL_WIDTH + (value - self.min) as f64 / span * (WIDTH - LABEL_WIDTH - MARGIN)
+    }
+
+    fn middle(&self, range: &Range<i64>) -> f64 {
+        (self.x(range.start) + self.x(range.end)) / 2.0
+    }
+}
+
+fn row_y(row: usize) -> f64 {
+    MARGIN + ROW_HEIGHT * row as f64 + ROW_HEIGHT / 2.0
+}
+
+/// Ranges present at every stage, the seeds go first
+fn stages(seeds: &[SrcRange], hops: &[Hop]) -> Vec<Vec<Range<i64>>> {
+    let mut stages = vec![seeds.to_vec()];
+    stages.extend(hops.iter().map(|hop| {
+        hop.ranges
+            .iter()
+            .flat_map(|trace| trace.fragments.iter().map(Fragment::destination))
+            .collect()
+    }));
+    stages
+}
+
+fn write_bars(svg: &mut String, scale: &Scale, row: usize, ranges: &[Range<i64>]) {
+    let y = row_y(row) - BAR_HEIGHT / 2.0;
+    for range in ranges.iter().filter(|range| !range.is_empty()) {
+        let x = scale.x(range.start);
+        let width = (scale.x(range.end) - x).max(MIN_BAR_WIDTH);
+        writeln!(
+            svg,
+            "<rect class=\"bar\" x=\"{x:.1}\" y=\"{y:.1}\" width=\"{width:.1}\" \
+             height=\"{BAR_HEIGHT}\"><title>{}</title></rect>",
+            Values(range)
+        )
+        .unwrap();
+    }
+}
+
+/// Hop between the rows `row` and `row + 1`: ticks where the ranges are split and an arrow
+/// per fragment
+fn write_hop(svg: &mut String, scale: &Scale, row: usize, hop: &Hop) {
+    let (from_y, to_y) = (row_y(row), row_y(row + 1));
+    for trace in &hop.ranges {
+        for fragment in trace.fragments.iter().skip(1) {
+            let x = scale.x(fragment.source.start);
+            writeln!(
+                svg,
+                "<line class=\"split\" x1=\"{x:.1}\" y1=\"{:.1}\" x2=\"{x:.1}\" y2=\"{:.1}\"/>",
+                from_y - BAR_HEIGHT / 2.0,
+                from_y + BAR_HEIGHT / 2.0
+            )
+            .unwrap();
+        }
+        for fragment in &trace.fragments {
+            let (class, line) = match fragment.line {
+                Some(line) => (
+                    "shifted",
+                    format!("line {line}, offset {:+}", fragment.offset),
+                ),
+                None => ("unmapped", "unmapped".to_owned()),
+            };
+            writeln!(
+                svg,
+                "<line class=\"{class}\" x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\">\
+                 <title>{} -> {} ({line})</title></line>",
+                scale.middle(&fragment.source),
+                from_y + BAR_HEIGHT / 2.0,
+                scale.middle(&fragment.destination()),
+                to_y - BAR_HEIGHT / 2.0 - 2.0,
+                Values(&fragment.source),
+                Values(&fragment.destination())
+            )
+            .unwrap();
+        }
+    }
+}
+
+/// Standalone SVG image, `source` is the category of the seeds
+pub fn render_svg(source: &str, seeds: &[SrcRange], hops: &[Hop]) -> String {
+    let stages = stages(seeds, hops);
+    let values = stages
+        .iter()
+        .flatten()
+        .flat_map(|range| [range.start, range.end]);
+    let scale = Scale {
+        min: values.clone().min().unwrap_or_default(),
+        max: values.max().unwrap_or_default(),
+    };
+    let height = 2.0 * MARGIN + ROW_HEIGHT * stages.len() as f64;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{WIDTH}\" height=\"{height}\" \
+         viewBox=\"0 0 {WIDTH} {height}\">\n<style>\n{SVG_STYLE}\n</style>\n\
+         <defs><marker id=\"arrow\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" \
+         markerWidth=\"6\" markerHeight=\"6\" orient=\"auto\">\
+         <path d=\"M 0 0 L 10 5 L 0 10 z\" fill=\"context-stroke\"/></marker></defs>\n"
+    );
+
+    let categories = [source]
+        .into_iter()
+        .chain(hops.iter().map(|hop| hop.dst_key.as_str()));
+    for (row, (category, ranges)) in categories.zip(&stages).enumerate() {
+        let y = row_y(row);
+        writeln!(
+            svg,
+            "<text x=\"{MARGIN}\" y=\"{:.1}\">{category} ({} ranges)</text>\n\
+             <line class=\"axis\" x1=\"{LABEL_WIDTH}\" y1=\"{y:.1}\" x2=\"{:.1}\" y2=\"{y:.1}\"/>",
+            y + 4.0,
+            ranges.len(),
+            WIDTH - MARGIN
+        )
+        .unwrap();
+    }
+    for (row, hop) in hops.iter().enumerate() {
+        write_hop(&mut svg, &scale, row, hop);
+    }
+    // Bars go last to stay above the arrows
+    for (row, ranges) in stages.iter().enumerate() {
+        write_bars(&mut svg, &scale, row, ranges);
+    }
+
+    writeln!(
+        svg,
+        "<text x=\"{LABEL_WIDTH}\" y=\"{:.1}\">{}</text>\n\
+         <text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"end\">{}</text>",
+        height - MARGIN / 2.0,
+        scale.min,
+        WIDTH - MARGIN,
+        height - MARGIN / 2.0,
+        scale.max
+    )
+    .unwrap();
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{almanac::parse_input, chain::SEED_CATEGORY, trace::trace_ranges};
+
+    const ALMANAC: &str = "\
+seeds: 40 70
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+";
+
+    #[test]
+    fn every_stage_and_fragment_is_drawn() {
+        let input = parse_input(ALMANAC).unwrap().1;
+        let seeds = vec![0..10, 40..110];
+        let hops = trace_ranges(&input, seeds.clone(), SEED_CATEGORY, "fertilizer").unwrap();
+        let svg = render_svg(SEED_CATEGORY, &seeds, &hops);
+
+        assert!(svg.starts_with("<svg ") && svg.ends_with("</svg>\n"));
+        assert!(svg.contains(">seed (2 ranges)<"));
+        assert!(svg.contains(">soil (5 ranges)<"));
+        assert!(svg.contains(">fertilizer (6 ranges)<"));
+
+        let fragments: usize = hops
+            .iter()
+            .flat_map(|hop| &hop.ranges)
+            .map(|trace| trace.fragments.len())
+            .sum();
+        let arrows =
+            svg.matches("class=\"shifted\"").count() + svg.matches("class=\"unmapped\"").count();
+        assert_eq!(arrows, fragments);
+    }
+}
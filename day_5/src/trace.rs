@@ -0,0 +1,229 @@
+//! Trace of seeds through every category of the chain: which mapping line moved each part of
+//! the range and by which offset
+
+use std::{fmt, ops::Range};
+
+use crate::{
+    almanac::{Input, MappingLine},
+    chain::{resolve_chain, ChainError},
+    range_map::{intersection, SrcRange},
+};
+
+/// Part of a range handled by a single mapping line
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fragment {
+    pub source: SrcRange,
+    /// Line of the block starting from 1, `None` if no line contains the part
+    pub line: Option<usize>,
+    pub offset: i64,
+}
+
+/// Range entering a hop and the fragments it has been split into, in the ascending order
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RangeTrace {
+    pub range: SrcRange,
+    pub fragments: Vec<Fragment>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hop {
+    pub src_key: String,
+    pub dst_key: String,
+    pub ranges: Vec<RangeTrace>,
+}
+
+impl Fragment {
+    pub fn destination(&self) -> Range<i64> {
+        self.source.start + self.offset..self.source.end + self.offset
+    }
+}
+
+/// Splits the range by the lines, the first line containing a value maps it
+fn split(range: SrcRange, lines: &[MappingLine]) -> Vec<Fragment> {
+    let mut fragments = Vec::new();
+    let mut pending = vec![range];
+
+    for (i, line) in lines.iter().enumerate() {
+        let (source, destination) = line.ranges();
+        pending = pending
+            .into_iter()
+            .flat_map(|range| {
+                let matched = intersection(&range, &source);
+                if matched.is_empty() {
+                    return vec![range];
+                }
+                fragments.push(Fragment {
+                    source: matched.clone(),
+                    line: Some(i + 1),
+                    offset: destination.start - source.start,
+                });
+                vec![range.start..matched.start, matched.end..range.end]
+            })
+            .filter(|range| !range.is_empty())
+            .collect();
+    }
+    fragments.extend(pending.into_iter().map(|source| Fragment {
+        source,
+        line: None,
+        offset: 0,
+    }));
+
+    fragments.sort_unstable_by_key(|fragment| fragment.source.start);
+    fragments
+}
+
+/// Follows the seeds from the `source` category to the `target` one hop by hop
+pub fn trace(
+    input: &Input,
+    seeds: SrcRange,
+    source: &str,
+    target: &str,
+) -> Result<Vec<Hop>, ChainError> {
+    trace_ranges(input, vec![seeds], source, target)
+}
+
+/// Same as `trace` for several seed ranges at once
+pub fn trace_ranges(
+    input: &Input,
+    seeds: Vec<SrcRange>,
+    source: &str,
+    target: &str,
+) -> Result<Vec<Hop>, ChainError> {
+    let chain = resolve_chain(input.links(), source, target)?;
+    let mut ranges = seeds;
+    let mut hops = Vec::with_capacity(chain.len());
+
+    for key in chain {
+        let lines = &input.transformations[&key];
+        let traces: Vec<RangeTrace> = ranges
+            .into_iter()
+            .map(|range| RangeTrace {
+                fragments: split(range.clone(), lines),
+                range,
+            })
+            .collect();
+        ranges = traces
+            .iter()
+            .flat_map(|trace| trace.fragments.iter().map(Fragment::destination))
+            .collect();
+        hops.push(Hop {
+            src_key: key.0.to_owned(),
+            dst_key: key.1.to_owned(),
+            ranges: traces,
+        });
+    }
+
+    Ok(hops)
+}
+
+/// Single values are written on their own, ranges are written as `start..end`
+pub struct Values<'a>(pub &'a Range<i64>);
+
+impl fmt::Display for Values<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.end - self.0.start == 1 {
+            write!(f, "{}", self.0.start)
+        } else {
+            write!(f, "{}..{}", self.0.start, self.0.end)
+        }
+    }
+}
+
+impl fmt::Display for Fragment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} -> {} ",
+            Values(&self.source),
+            Values(&self.destination())
+        )?;
+        match self.line {
+            Some(line) => write!(f, "(line {line}, offset {:+})", self.offset),
+            None => f.write_str("(unmapped)"),
+        }
+    }
+}
+
+impl fmt::Display for Hop {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}-to-{}:", self.src_key, self.dst_key)?;
+        for trace in &self.ranges {
+            match trace.fragments.as_slice() {
+                [fragment] => writeln!(f, "  {fragment}")?,
+                fragments => {
+                    writeln!(
+                        f,
+                        "  {} split into {}:",
+                        Values(&trace.range),
+                        fragments.len()
+                    )?;
+                    for fragment in fragments {
+                        writeln!(f, "    {fragment}")?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        almanac::parse_input,
+        chain::{LOCATION_CATEGORY, SEED_CATEGORY},
+    };
+
+    const ALMANAC: &str = "\
+seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+";
+
+    #[test]
+    fn range_split_is_attributed_to_lines() {
+        let input = parse_input(ALMANAC).unwrap().1;
+        let hops = trace(&input, 40..110, SEED_CATEGORY, "soil").unwrap();
+        assert_eq!(
+            hops[0].to_string(),
+            "\
+seed-to-soil:
+  40..110 split into 4:
+    40..50 -> 40..50 (unmapped)
+    50..98 -> 52..100 (line 2, offset +2)
+    98..100 -> 50..52 (line 1, offset -48)
+    100..110 -> 100..110 (unmapped)
+"
+        );
+    }
+
+    #[test]
+    fn traced_ranges_match_collapsed_map() {
+        let input = parse_input(ALMANAC).unwrap().1;
+        let target = "fertilizer";
+        let collapsed = input.collapse(SEED_CATEGORY, target).unwrap();
+
+        for seeds in [79..80, 0..120, 50..54] {
+            let hops = trace(&input, seeds.clone(), SEED_CATEGORY, target).unwrap();
+            let mut traced: Vec<i64> = hops[hops.len() - 1]
+                .ranges
+                .iter()
+                .flat_map(|trace| trace.fragments.iter().flat_map(Fragment::destination))
+                .collect();
+            traced.sort_unstable();
+
+            let mut expected: Vec<i64> = collapsed.map_range(seeds).into_iter().flatten().collect();
+            expected.sort_unstable();
+            assert_eq!(traced, expected);
+        }
+        assert!(trace(&input, 0..1, SEED_CATEGORY, LOCATION_CATEGORY).is_err());
+    }
+}
@@ -0,0 +1,298 @@
+//! Almanac checks and normalisation
+//!
+//! Seeds are expected to fit into `i64` whichever way they're read. Every `src-to-dst` block
+//! is expected to be given once, its mapping lines are expected to have non-empty source ranges
+//! that don't overlap and fit into `i64`. The normalised almanac gives the same answers as the
+//! original one: its blocks are written as their `RangeMap` segments, so adjacent lines with
+//! equal offsets are merged, identity lines are dropped and overlaps are resolved in favour of
+//! the first line.
+
+use std::{error::Error, fmt};
+
+use crate::{
+    almanac::{Input, MappingLine, SeedMode},
+    range_map::{intersection, RangeMap},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Issue {
+    /// Ends of the ranges don't fit into `i64`, lines are numbered from 1 within the block
+    Overflow {
+        block: String,
+        line: usize,
+    },
+    EmptyRange {
+        block: String,
+        line: usize,
+    },
+    /// Source ranges of the lines `first` and `second` overlap
+    Overlap {
+        block: String,
+        first: usize,
+        second: usize,
+    },
+    /// Seeds read in the `mode` don't fit into `i64`, numbers of the `seeds:` line are
+    /// counted from 1
+    SeedOverflow {
+        mode: SeedMode,
+        number: usize,
+    },
+    /// Block is given again, only its first occurrence is kept
+    RepeatedBlock {
+        block: String,
+    },
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::Overflow { block, line } => {
+                write!(f, "{block} map, line {line}: range end overflows i64")
+            }
+            Issue::EmptyRange { block, line } => {
+                write!(f, "{block} map, line {line}: range is empty")
+            }
+            Issue::Overlap {
+                block,
+                first,
+                second,
+            } => write!(
+                f,
+                "{block} map, lines {first} and {second}: source ranges overlap"
+            ),
+            Issue::SeedOverflow {
+                mode: SeedMode::Individual,
+                number,
+            } => write!(f, "seeds, number {number}: next seed overflows i64"),
+            Issue::SeedOverflow {
+                mode: SeedMode::Ranges,
+                number,
+            } => write!(
+                f,
+                "seeds, numbers {number} and {}: range end overflows i64",
+                number + 1
+            ),
+            Issue::RepeatedBlock { block } => {
+                write!(f, "{block} map is given more than once")
+            }
+        }
+    }
+}
+
+impl Error for Issue {}
+
+pub fn validate(input: &Input) -> Vec<Issue> {
+    let mut issues = Vec::new();
+
+    let seeds = &input.initial_seeds;
+    for (i, seed) in seeds.iter().enumerate() {
+        if seed.checked_add(1).is_none() {
+            issues.push(Issue::SeedOverflow {
+                mode: SeedMode::Individual,
+                number: i + 1,
+            });
+        }
+    }
+    for (k, pair) in seeds.chunks_exact(2).enumerate() {
+        if pair[0].checked_add(pair[1]).is_none() {
+            issues.push(Issue::SeedOverflow {
+                mode: SeedMode::Ranges,
+                number: 2 * k + 1,
+            });
+        }
+    }
+
+    for ((src_key, dst_key), lines) in input.blocks() {
+        let block = format!("{src_key}-to-{dst_key}");
+        let mut sources = Vec::with_capacity(lines.len());
+
+        for (i, line) in lines.iter().enumerate() {
+            match line.checked_ranges() {
+                None => issues.push(Issue::Overflow {
+                    block: block.clone(),
+                    line: i + 1,
+                }),
+                Some((source, _)) if source.is_empty() => issues.push(Issue::EmptyRange {
+                    block: block.clone(),
+                    line: i + 1,
+                }),
+                Some((source, _)) => sources.push((i + 1, source)),
+            }
+        }
+
+        for (k, (first, first_source)) in sources.iter().enumerate() {
+            for (second, second_source) in &sources[k + 1..] {
+                if !intersection(first_source, second_source).is_empty() {
+                    issues.push(Issue::Overlap {
+                        block: block.clone(),
+                        first: *first,
+                        second: *second,
+                    });
+                }
+            }
+        }
+    }
+
+    issues.extend(
+        input
+            .repeated_blocks
+            .iter()
+            .map(|(src_key, dst_key)| Issue::RepeatedBlock {
+                block: format!("{src_key}-to-{dst_key}"),
+            }),
+    );
+
+    issues
+}
+
+/// Almanac with every block replaced by its segments, lines mustn't overflow and blocks
+/// mustn't be repeated
+pub fn normalize<'a>(input: &Input<'a>) -> Input<'a> {
+    Input {
+        initial_seeds: input.initial_seeds.clone(),
+        transformations: input
+            .transformations
+            .iter()
+            .map(|(key, lines)| {
+                let map = RangeMap::new(lines.iter().map(MappingLine::ranges));
+                let lines = map
+                    .segments()
+                    .iter()
+                    .map(|segment| MappingLine {
+                        destination: segment.source.start + segment.offset,
+                        source: segment.source.start,
+                        length: segment.source.end - segment.source.start,
+                    })
+                    .collect();
+                (*key, lines)
+            })
+            .collect(),
+        repeated_blocks: input.repeated_blocks.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        almanac::{parse_input, SeedMode},
+        chain::{LOCATION_CATEGORY, SEED_CATEGORY},
+    };
+
+    /// Lines 1 and 2 are adjacent with equal offsets, line 3 is an identity and line 4
+    /// overlaps the line 1
+    const ALMANAC: &str = "\
+seeds: 79 14 55 13
+
+soil-to-location map:
+0 69 1
+1 0 69
+
+seed-to-soil map:
+52 50 10
+62 60 38
+110 110 5
+0 55 3
+50 98 2
+0 120 0
+";
+
+    #[test]
+    fn issues_are_reported() {
+        let input = parse_input(ALMANAC).unwrap().1;
+        assert_eq!(
+            validate(&input),
+            vec![
+                Issue::EmptyRange {
+                    block: "seed-to-soil".to_owned(),
+                    line: 6
+                },
+                Issue::Overlap {
+                    block: "seed-to-soil".to_owned(),
+                    first: 1,
+                    second: 4
+                },
+            ]
+        );
+
+        let input = parse_input("seeds: 1\n\na-to-b map:\n9223372036854775807 0 1\n")
+            .unwrap()
+            .1;
+        assert_eq!(
+            validate(&input),
+            vec![Issue::Overflow {
+                block: "a-to-b".to_owned(),
+                line: 1
+            }]
+        );
+
+        let input =
+            parse_input("seeds: 9223372036854775807 5 1 9223372036854775807\n\na-to-b map:\n")
+                .unwrap()
+                .1;
+        assert_eq!(
+            validate(&input),
+            vec![
+                Issue::SeedOverflow {
+                    mode: SeedMode::Individual,
+                    number: 1
+                },
+                Issue::SeedOverflow {
+                    mode: SeedMode::Individual,
+                    number: 4
+                },
+                Issue::SeedOverflow {
+                    mode: SeedMode::Ranges,
+                    number: 1
+                },
+                Issue::SeedOverflow {
+                    mode: SeedMode::Ranges,
+                    number: 3
+                },
+            ]
+        );
+
+        let input = parse_input("seeds: 1\n\na-to-b map:\n0 5 1\n\na-to-b map:\n0 9 1\n")
+            .unwrap()
+            .1;
+        assert_eq!(
+            validate(&input),
+            vec![Issue::RepeatedBlock {
+                block: "a-to-b".to_owned()
+            }]
+        );
+    }
+
+    #[test]
+    fn normalized_almanac_is_canonical_and_equivalent() {
+        let input = parse_input(ALMANAC).unwrap().1;
+        let normalized = normalize(&input).to_string();
+        assert_eq!(
+            normalized,
+            "\
+seeds: 79 14 55 13
+
+seed-to-soil map:
+52 50 48
+50 98 2
+
+soil-to-location map:
+1 0 69
+0 69 1
+"
+        );
+
+        let reparsed = parse_input(&normalized).unwrap().1;
+        assert!(validate(&reparsed).is_empty());
+        assert_eq!(normalize(&reparsed).to_string(), normalized);
+        for (source, target) in [(SEED_CATEGORY, LOCATION_CATEGORY), ("soil", "location")] {
+            assert_eq!(
+                reparsed.collapse(source, target),
+                input.collapse(source, target)
+            );
+        }
+        for mode in [SeedMode::Individual, SeedMode::Ranges] {
+            assert_eq!(reparsed.seed_ranges(mode), input.seed_ranges(mode));
+        }
+    }
+}
@@ -0,0 +1,271 @@
+//! Rectangular grid shared by the days working with 2D maps
+
+use std::ops::{Index, IndexMut};
+
+use nom::{
+    character::complete::line_ending,
+    combinator::verify,
+    multi::{many1, separated_list1},
+    IResult,
+};
+
+/// `(line, column)` of a cell
+pub type Position = (usize, usize);
+
+/// Shifts to the vertical and horizontal neighbours
+pub const OFFSETS_4: [(isize, isize); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+/// Shifts to the vertical, horizontal and diagonal neighbours
+pub const OFFSETS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    lines: usize,
+    columns: usize,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its lines, returns `None` if they have different lengths
+    pub fn from_lines(lines: Vec<Vec<T>>) -> Option<Self> {
+        let columns = lines.first().map_or(0, Vec::len);
+        if lines.iter().any(|line| line.len() != columns) {
+            return None;
+        }
+        Some(Grid {
+            lines: lines.len(),
+            columns,
+            cells: lines.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn lines(&self) -> usize {
+        self.lines
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn contains(&self, (i, j): Position) -> bool {
+        i < self.lines && j < self.columns
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        self.contains(position)
+            .then(|| &self.cells[position.0 * self.columns + position.1])
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        self.contains(position)
+            .then(|| &mut self.cells[position.0 * self.columns + position.1])
+    }
+
+    /// Position shifted by the `offset`, `None` if it goes beyond the grid
+    pub fn shift(&self, (i, j): Position, (m, n): (isize, isize)) -> Option<Position> {
+        let position = (i.checked_add_signed(m)?, j.checked_add_signed(n)?);
+        self.contains(position).then_some(position)
+    }
+
+    /// Vertical and horizontal neighbours of the `position` lying within the grid
+    pub fn neighbors4(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        OFFSETS_4
+            .into_iter()
+            .filter_map(move |offset| self.shift(position, offset))
+    }
+
+    /// Vertical, horizontal and diagonal neighbours of the `position` lying within the grid
+    pub fn neighbors8(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        OFFSETS_8
+            .into_iter()
+            .filter_map(move |offset| self.shift(position, offset))
+    }
+
+    pub fn line(&self, i: usize) -> Option<&[T]> {
+        (i < self.lines).then(|| &self.cells[i * self.columns..(i + 1) * self.columns])
+    }
+
+    pub fn column(&self, j: usize) -> Option<impl Iterator<Item = &T>> {
+        (j < self.columns).then(|| self.cells.iter().skip(j).step_by(self.columns))
+    }
+
+    pub fn iter_lines(&self) -> impl Iterator<Item = &[T]> {
+        // `max` keeps `chunks` away from the zero-sized chunks of an empty grid
+        self.cells.chunks(self.columns.max(1))
+    }
+
+    /// Cells along with their positions, line by line
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(k, cell)| ((k / self.columns, k % self.columns), cell))
+    }
+
+    /// Position of the first cell (line by line) satisfying the `predicate`
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<Position> {
+        self.iter()
+            .find(|(_, cell)| predicate(cell))
+            .map(|(position, _)| position)
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &Self::Output {
+        self.get(position).expect("position is out of the grid")
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut Self::Output {
+        self.get_mut(position).expect("position is out of the grid")
+    }
+}
+
+/// Parses `\n` or `\r\n` separated lines of the cells recognized by the `cell_parser`,
+/// the lines must have the same length
+pub fn parse_grid<'a, T>(
+    cell_parser: impl FnMut(&'a str) -> IResult<&'a str, T>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Grid<T>> {
+    let mut lines_parser = verify(
+        separated_list1(line_ending, many1(cell_parser)),
+        |lines: &Vec<Vec<T>>| lines.iter().all(|line| line.len() == lines[0].len()),
+    );
+    move |input| {
+        let (input, lines) = lines_parser(input)?;
+        // Lengths are verified above
+        Ok((input, Grid::from_lines(lines).unwrap()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nom::character::complete::{none_of, one_of};
+
+    use super::*;
+
+    /// 3x4 grid of the cells `10 * line + column`
+    fn grid() -> Grid<usize> {
+        Grid::from_lines(
+            (0..3)
+                .map(|i| (0..4).map(|j| 10 * i + j).collect())
+                .collect(),
+        )
+        .unwrap()
+    }
+
+    fn sorted(positions: impl Iterator<Item = Position>) -> Vec<Position> {
+        let mut positions: Vec<Position> = positions.collect();
+        positions.sort_unstable();
+        positions
+    }
+
+    #[test]
+    fn shift_stays_within_the_grid() {
+        let grid = grid();
+        assert_eq!(grid.shift((0, 0), (1, 1)), Some((1, 1)));
+        assert_eq!(grid.shift((0, 0), (-1, 0)), None);
+        assert_eq!(grid.shift((0, 0), (0, -1)), None);
+        assert_eq!(grid.shift((2, 3), (1, 0)), None);
+        assert_eq!(grid.shift((2, 3), (0, 1)), None);
+        assert_eq!(grid.shift((0, 3), (-1, 1)), None);
+        assert_eq!(grid.shift((2, 0), (1, -1)), None);
+        assert_eq!(grid.shift((2, 3), (-2, -3)), Some((0, 0)));
+        assert_eq!(grid.shift((1, 1), (isize::MIN, 0)), None);
+    }
+
+    #[test]
+    fn corners_have_fewer_neighbors() {
+        let grid = grid();
+        let corners = [
+            ((0, 0), vec![(0, 1), (1, 0)], vec![(0, 1), (1, 0), (1, 1)]),
+            ((0, 3), vec![(0, 2), (1, 3)], vec![(0, 2), (1, 2), (1, 3)]),
+            ((2, 0), vec![(1, 0), (2, 1)], vec![(1, 0), (1, 1), (2, 1)]),
+            ((2, 3), vec![(1, 3), (2, 2)], vec![(1, 2), (1, 3), (2, 2)]),
+        ];
+        for (corner, neighbors4, neighbors8) in corners {
+            assert_eq!(sorted(grid.neighbors4(corner)), neighbors4);
+            assert_eq!(sorted(grid.neighbors8(corner)), neighbors8);
+        }
+    }
+
+    #[test]
+    fn edges_and_inner_cells_have_their_neighbors() {
+        let grid = grid();
+        let edges = [
+            ((0, 1), 3, 5),
+            ((2, 2), 3, 5),
+            ((1, 0), 3, 5),
+            ((1, 3), 3, 5),
+            ((1, 1), 4, 8),
+        ];
+        for (position, neighbors4, neighbors8) in edges {
+            assert_eq!(grid.neighbors4(position).count(), neighbors4);
+            assert_eq!(grid.neighbors8(position).count(), neighbors8);
+            assert!(grid
+                .neighbors8(position)
+                .all(|neighbor| grid.contains(neighbor)));
+        }
+        assert_eq!(
+            sorted(grid.neighbors4((0, 1))),
+            vec![(0, 0), (0, 2), (1, 1)]
+        );
+    }
+
+    #[test]
+    fn lines_columns_and_cells_are_accessible() {
+        let mut grid = grid();
+        assert_eq!((grid.lines(), grid.columns()), (3, 4));
+        assert_eq!(grid.line(1), Some([10, 11, 12, 13].as_slice()));
+        assert_eq!(grid.line(3), None);
+        assert_eq!(
+            grid.column(2)
+                .map(|column| column.copied().collect::<Vec<_>>()),
+            Some(vec![2, 12, 22])
+        );
+        assert!(grid.column(4).is_none());
+        assert!(grid.iter().all(|((i, j), cell)| *cell == 10 * i + j));
+        assert_eq!(grid.iter().count(), 12);
+        assert_eq!(grid.iter_lines().count(), 3);
+        assert_eq!(grid.position(|cell| *cell > 20), Some((2, 1)));
+
+        grid[(2, 3)] = 0;
+        assert_eq!(grid.get((2, 3)), Some(&0));
+        assert_eq!(grid.get((3, 0)), None);
+    }
+
+    #[test]
+    fn empty_grid_has_no_cells() {
+        let grid: Grid<u8> = Grid::from_lines(Vec::new()).unwrap();
+        assert_eq!((grid.lines(), grid.columns()), (0, 0));
+        assert_eq!(grid.iter().count(), 0);
+        assert_eq!(grid.iter_lines().count(), 0);
+        assert_eq!(grid.line(0), None);
+        assert!(grid.column(0).is_none());
+        assert!(!grid.contains((0, 0)));
+    }
+
+    #[test]
+    fn grid_is_parsed() {
+        let expected = Grid::from_lines(vec![vec!['a', 'b'], vec!['c', 'd']]).unwrap();
+        for input in ["ab\ncd", "ab\r\ncd"] {
+            let (rest, grid) = parse_grid(none_of("\r\n"))(input).unwrap();
+            assert_eq!((rest, grid), ("", expected.clone()));
+        }
+
+        assert!(Grid::from_lines(vec![vec![1, 2], vec![3]]).is_none());
+        assert!(parse_grid(one_of("#."))("##\n#\n##").is_err());
+        assert!(parse_grid(one_of("#."))("").is_err());
+    }
+}